@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use crate::util::{lines, parse_file, read_text, InputError, ParseError};
+use crate::{solvers, Part};
+
+pub type Answers = BTreeMap<(u32, Part), String>;
+
+fn parse_section(line: &str) -> Result<u32, ParseError> {
+    line.strip_prefix("[day")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .and_then(|day| day.parse().ok())
+        .ok_or_else(|| ParseError::new(1, line, "a section like `[day01]`"))
+}
+
+fn parse_key(line: &str) -> Result<(Part, &str), ParseError> {
+    let (key, value) = line
+        .split_once(" = ")
+        .ok_or_else(|| ParseError::new(1, line, "an answer like `part1 = \"42\"`"))?;
+    let part = match key {
+        "part1" => Part::One,
+        "part2" => Part::Two,
+        _ => return Err(ParseError::new(1, key, "`part1` or `part2`")),
+    };
+    Ok((part, value))
+}
+
+/// Reads the subset of TOML used by `answers.toml`: `[dayNN]` sections
+/// holding `part1` and `part2` as strings, which may be `"""` multi-line.
+pub fn from_str(input: &str) -> Result<Answers, ParseError> {
+    let mut answers = Answers::new();
+    let mut day = None;
+    let mut lines = lines(input).enumerate();
+    while let Some((index, line)) = lines.next() {
+        let at_line = |e: ParseError| e.at_line(index + 1);
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            day = Some(parse_section(&line).map_err(at_line)?);
+            continue;
+        }
+        let (part, value) = parse_key(&line).map_err(at_line)?;
+        let day = day.ok_or_else(|| at_line(ParseError::new(1, &line, "a `[dayNN]` section")))?;
+        let answer = if value == "\"\"\"" {
+            let mut answer = vec![];
+            loop {
+                match lines.next() {
+                    Some((_, line)) if line == "\"\"\"" => break,
+                    Some((_, line)) => answer.push(line),
+                    None => return Err(at_line(ParseError::new(1, value, "a closing `\"\"\"`"))),
+                }
+            }
+            answer.join("\n")
+        } else {
+            value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .ok_or_else(|| at_line(ParseError::new(1, value, "a quoted answer")))?
+                .to_string()
+        };
+        answers.insert((day, part), answer);
+    }
+    Ok(answers)
+}
+
+pub fn parse_answers(file: &str) -> Result<Answers, InputError> {
+    parse_file(file, from_str)
+}
+
+// Trailing spaces are easily lost from a checked-in file, so ignore them.
+fn normalise(answer: &str) -> String {
+    answer
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Runs every day in `answers` on its `dayNN.txt`, returning a description
+/// of each answer that is missing or has changed.
+pub fn check(answers: &Answers) -> Vec<String> {
+    let solvers = solvers();
+    answers
+        .iter()
+        .filter_map(|((day, part), expected)| {
+            let file = format!("day{:02}.txt", day);
+            let actual = match (solvers.get(day), read_text(&file)) {
+                (None, _) => return Some(format!("day {}: no solution", day)),
+                (_, Err(e)) => return Some(format!("day {}: cannot read `{}`: {}", day, file, e)),
+                (Some(solver), Ok(input)) => solver.solve(&input, *part),
+            };
+            match actual {
+                Ok(actual) if normalise(&actual) == normalise(expected) => None,
+                Ok(actual) => Some(format!(
+                    "day {} {:?}: expected `{}`, found `{}`",
+                    day, part, expected, actual
+                )),
+                Err(e) => Some(format!("day {} {:?}: {}", day, part, e.in_file(&file))),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let answers =
+            from_str("# comment\n[day03]\npart1 = \"12\"\npart2 = \"\"\"\n#.\n.#\n\"\"\"\n")
+                .unwrap();
+        assert_eq!(Some(&"12".to_string()), answers.get(&(3, Part::One)));
+        assert_eq!(Some(&"#.\n.#".to_string()), answers.get(&(3, Part::Two)));
+    }
+
+    #[test]
+    fn test_from_str_error() {
+        assert_eq!(
+            ParseError::new(1, "part3", "`part1` or `part2`").at_line(2),
+            from_str("[day01]\npart3 = \"1\"").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "part1 = \"1\"", "a `[dayNN]` section"),
+            from_str("part1 = \"1\"").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "\"\"\"", "a closing `\"\"\"`").at_line(2),
+            from_str("[day13]\npart2 = \"\"\"\n#..#").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let mut answers = from_str("[day01]\npart1 = \"1154\"\npart2 = \"1\"\n").unwrap();
+        assert_eq!(
+            vec!["day 1 Two: expected `1`, found `1127`".to_string()],
+            check(&answers)
+        );
+        answers.insert((30, Part::One), "0".to_string());
+        assert_eq!(2, check(&answers).len());
+    }
+
+    #[test]
+    fn test_answers() {
+        let answers = parse_answers("answers.toml").unwrap();
+        let mismatches = check(&answers);
+        assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+    }
+}
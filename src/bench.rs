@@ -0,0 +1,112 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::{Solution, SolveError};
+
+pub const PHASES: [&str; 3] = ["parse", "part 1", "part 2"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stats {
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn of(samples: &[Duration]) -> Stats {
+        Stats {
+            mean: samples.iter().sum::<Duration>() / samples.len().max(1) as u32,
+            min: samples.iter().min().copied().unwrap_or_default(),
+            max: samples.iter().max().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Times parsing and both parts of `solution` over `runs` runs on `input`.
+pub fn bench(solution: &dyn Solution, input: &str, runs: u32) -> Result<[Stats; 3], SolveError> {
+    let mut samples: [Vec<Duration>; 3] = Default::default();
+    for _ in 0..runs {
+        for (phase, time) in samples.iter_mut().zip(solution.time(input)?.iter()) {
+            phase.push(*time);
+        }
+    }
+    Ok([
+        Stats::of(&samples[0]),
+        Stats::of(&samples[1]),
+        Stats::of(&samples[2]),
+    ])
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+/// One row per day and phase, with the mean, fastest and slowest run.
+pub fn format_table(rows: &[(u32, [Stats; 3])]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}\n",
+        "day", "phase", "mean", "min", "max"
+    );
+    for (day, stats) in rows {
+        for (phase, stats) in PHASES.iter().zip(stats.iter()) {
+            writeln!(
+                table,
+                "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}",
+                day,
+                phase,
+                format_duration(stats.mean),
+                format_duration(stats.min),
+                format_duration(stats.max)
+            )
+            .unwrap();
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [3, 1, 8].map(Duration::from_millis);
+        assert_eq!(
+            Stats {
+                mean: Duration::from_millis(4),
+                min: Duration::from_millis(1),
+                max: Duration::from_millis(8),
+            },
+            Stats::of(&samples)
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!("999ns", format_duration(Duration::from_nanos(999)));
+        assert_eq!("1.5µs", format_duration(Duration::from_nanos(1_500)));
+        assert_eq!("12.0ms", format_duration(Duration::from_millis(12)));
+        assert_eq!("2.50s", format_duration(Duration::from_millis(2_500)));
+    }
+
+    #[test]
+    fn test_bench() {
+        let solvers = crate::solvers();
+        let stats = bench(solvers[&1].as_ref(), "1\n2\n3\n4\n", 3).unwrap();
+        assert!(stats.iter().all(|s| s.min <= s.mean && s.mean <= s.max));
+        assert!(bench(solvers[&1].as_ref(), "x\n", 3).is_err());
+
+        let table = format_table(&[(1, stats)]);
+        assert_eq!(4, table.lines().count());
+        assert!(table.lines().nth(2).unwrap().starts_with("  1  part 1"));
+    }
+}
@@ -1,6 +1,20 @@
+use crate::util::{lines, parse_i32, try_parse_lines, ParseError};
+use crate::{SolveError, Solver};
+
+pub fn from_str(input: &str) -> Result<Vec<i32>, ParseError> {
+    let depths = try_parse_lines(lines(input), parse_i32)?;
+    if depths.is_empty() {
+        return Err(ParseError::new(1, "", "a number"));
+    }
+    Ok(depths)
+}
+
 pub fn part1(mut input: impl Iterator<Item = i32>) -> i32 {
     let mut count: i32 = 0;
-    let mut last: i32 = input.next().unwrap();
+    let mut last: i32 = match input.next() {
+        Some(first) => first,
+        None => return 0,
+    };
 
     for value in input {
         if value > last {
@@ -14,8 +28,11 @@ pub fn part1(mut input: impl Iterator<Item = i32>) -> i32 {
 
 pub fn part2(mut input: impl Iterator<Item = i32>) -> i32 {
     let mut oldest = 0;
-    let mut middle = input.next().unwrap();
-    let mut newest = input.next().unwrap();
+    // Too few depths for a window means nothing can increase.
+    let (mut middle, mut newest) = match (input.next(), input.next()) {
+        (Some(middle), Some(newest)) => (middle, newest),
+        _ => return 0,
+    };
 
     let windowed = input.map(|input| {
         oldest = middle;
@@ -27,6 +44,26 @@ pub fn part2(mut input: impl Iterator<Item = i32>) -> i32 {
     part1(windowed)
 }
 
+pub struct Day01;
+
+impl Solver for Day01 {
+    type Input = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i32, SolveError> {
+        Ok(part1(input.iter().copied()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i32, SolveError> {
+        Ok(part2(input.iter().copied()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -51,6 +88,16 @@ mod test {
         assert_eq!(5, part2(EXAMPLE_INPUT.iter().cloned()));
     }
 
+    #[test]
+    fn test_short_input() {
+        assert_eq!(
+            ParseError::new(1, "", "a number"),
+            from_str("").unwrap_err()
+        );
+        assert_eq!(0, part1([1].iter().cloned()));
+        assert_eq!(0, part2([1, 2].iter().cloned()));
+    }
+
     #[test]
     fn exec_day01_part2() {
         let i = read_file_as_i32("day01.txt").unwrap();
@@ -1,3 +1,6 @@
+use crate::util::{column_of, lines, parse_field, try_parse_lines, ParseError};
+use crate::{SolveError, Solver};
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Direction {
     Forward,
@@ -5,8 +8,8 @@ pub enum Direction {
     Down,
 }
 
-pub fn parse_direction(input: String) -> Option<Direction> {
-    match input.as_ref() {
+pub fn parse_direction(input: &str) -> Option<Direction> {
+    match input {
         "forward" => Some(Direction::Forward),
         "up" => Some(Direction::Up),
         "down" => Some(Direction::Down),
@@ -14,17 +17,28 @@ pub fn parse_direction(input: String) -> Option<Direction> {
     }
 }
 
-pub fn parse_record(line: String) -> Option<Record> {
+pub fn parse_record(line: &str) -> Result<Record, ParseError> {
+    let end_of_line = &line[line.len()..];
     let mut parts = line.split_whitespace();
-    let direction_part = parts.next()?;
-    let direction = parse_direction(direction_part.to_string())?;
-    let number_part = parts.next()?;
-    let number = number_part.to_string().parse().ok()?;
-    Some((direction, number))
+    let direction_part = parts.next().unwrap_or(end_of_line);
+    let direction = parse_direction(direction_part).ok_or_else(|| {
+        ParseError::new(
+            column_of(line, direction_part),
+            direction_part,
+            "forward, up or down",
+        )
+    })?;
+    let number_part = parts.next().unwrap_or(end_of_line);
+    let number = parse_field(line, number_part, "a number")?;
+    Ok((direction, number))
 }
 
 pub type Record = (Direction, i32);
 
+pub fn from_str(input: &str) -> Result<Vec<Record>, ParseError> {
+    try_parse_lines(lines(input), parse_record)
+}
+
 pub fn part1(input: impl Iterator<Item = Record>) -> i32 {
     let mut horizontal = 0;
     let mut depth = 0;
@@ -59,10 +73,30 @@ pub fn part2(input: impl Iterator<Item = Record>) -> i32 {
     horizontal * depth
 }
 
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input = Vec<Record>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i32, SolveError> {
+        Ok(part1(input.iter().copied()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i32, SolveError> {
+        Ok(part2(input.iter().copied()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::util::read_lines;
+    use crate::util::try_read_lines;
 
     #[test]
     fn day02_parser() {
@@ -74,33 +108,44 @@ mod test {
             (Direction::Down, 8),
             (Direction::Forward, 2),
         ];
-        let result: Vec<(Direction, i32)> =
-            read_lines("day02.example", parse_record).unwrap().collect();
+        let result = try_read_lines("day02.example", parse_record).unwrap();
 
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn day02_parser_error() {
+        assert_eq!(
+            ParseError::new(1, "fowrard", "forward, up or down"),
+            parse_record("fowrard 5").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(3, "", "a number"),
+            parse_record("up").unwrap_err()
+        );
+    }
+
     #[test]
     fn example_day02_part1() {
-        let input = read_lines("day02.example", parse_record).unwrap();
-        assert_eq!(150, part1(input))
+        let input = try_read_lines("day02.example", parse_record).unwrap();
+        assert_eq!(150, part1(input.into_iter()))
     }
 
     #[test]
     fn exec_day02_part1() {
-        let input = read_lines("day02.txt", parse_record).unwrap();
-        println!("Day 2 Part 1: {}", part1(input))
+        let input = try_read_lines("day02.txt", parse_record).unwrap();
+        println!("Day 2 Part 1: {}", part1(input.into_iter()))
     }
 
     #[test]
     fn example_day02_part2() {
-        let input = read_lines("day02.example", parse_record).unwrap();
-        assert_eq!(900, part2(input))
+        let input = try_read_lines("day02.example", parse_record).unwrap();
+        assert_eq!(900, part2(input.into_iter()))
     }
 
     #[test]
     fn exec_day02_part2() {
-        let input = read_lines("day02.txt", parse_record).unwrap();
-        println!("Day 2 Part 2: {}", part2(input))
+        let input = try_read_lines("day02.txt", parse_record).unwrap();
+        println!("Day 2 Part 2: {}", part2(input.into_iter()))
     }
 }
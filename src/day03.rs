@@ -1,14 +1,19 @@
-pub fn line_parser(input: String) -> Option<Vec<bool>> {
-    Some(
-        input
-            .chars()
-            .map(|c| match c {
-                '0' => false,
-                '1' => true,
-                _ => unimplemented!(),
-            })
-            .collect(),
-    )
+use crate::util::{lines, try_parse_lines, ParseError};
+use crate::{SolveError, Solver};
+
+pub fn line_parser(input: &str) -> Result<Vec<bool>, ParseError> {
+    input
+        .char_indices()
+        .map(|(i, c)| match c {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            _ => Err(ParseError::new(i + 1, &c.to_string(), "0 or 1")),
+        })
+        .collect()
+}
+
+pub fn from_str(input: &str) -> Result<Vec<Vec<bool>>, ParseError> {
+    try_parse_lines(lines(input), line_parser)
 }
 
 fn most_common(input: Vec<bool>) -> Option<bool> {
@@ -59,17 +64,42 @@ pub fn part2(input: Vec<Vec<bool>>, use_least_common: bool) -> u32 {
     result
 }
 
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Vec<Vec<bool>>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part2(input.clone(), false) * part2(input.clone(), true))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::util::read_lines;
+    use crate::util::try_read_lines;
 
     #[test]
     fn test_day03_parser() {
-        let mut input = read_lines("day03.example", line_parser).unwrap();
+        let input = try_read_lines("day03.example", line_parser).unwrap();
+        assert_eq!(vec![false, false, true, false, false], input[0]);
+    }
+
+    #[test]
+    fn test_day03_parser_error() {
         assert_eq!(
-            vec![false, false, true, false, false],
-            input.next().unwrap()
+            ParseError::new(3, "2", "0 or 1"),
+            line_parser("01201").unwrap_err()
         );
     }
 
@@ -84,26 +114,26 @@ mod test {
 
     #[test]
     fn example_day03_part1() {
-        let input = read_lines("day03.example", line_parser).unwrap().collect();
+        let input = try_read_lines("day03.example", line_parser).unwrap();
         assert_eq!(198, part1(input));
     }
 
     #[test]
     fn exec_day03_part1() {
-        let input = read_lines("day03.txt", line_parser).unwrap().collect();
+        let input = try_read_lines("day03.txt", line_parser).unwrap();
         println!("Day 03, Part 1: {}", part1(input));
     }
 
     #[test]
     fn example_day03_part2() {
-        let input: Vec<Vec<bool>> = read_lines("day03.example", line_parser).unwrap().collect();
+        let input = try_read_lines("day03.example", line_parser).unwrap();
         assert_eq!(23, part2(input.clone(), false));
         assert_eq!(10, part2(input.clone(), true));
     }
 
     #[test]
     fn exec_day03_part2() {
-        let input: Vec<Vec<bool>> = read_lines("day03.txt", line_parser).unwrap().collect();
+        let input = try_read_lines("day03.txt", line_parser).unwrap();
         println!(
             "Day 03, Part 1: {}",
             part2(input.clone(), false) * part2(input, true)
@@ -1,46 +1,71 @@
 use std::collections::HashSet;
 
-pub fn parse_input(input: &mut impl Iterator<Item = String>) -> (Calls, Vec<Board>) {
-    let calls = parse_calls(input.next().unwrap());
-    input.next();
+use crate::util::{expect_blank, lines, parse_field, parse_line, ParseError};
+use crate::{SolveError, Solver};
+
+pub fn parse_input(
+    input: &mut impl Iterator<Item = String>,
+) -> Result<(Calls, Vec<Board>), ParseError> {
+    let mut input = input.enumerate().map(|(index, line)| (index + 1, line));
+
+    let (line_number, first_line) = input.next().unwrap_or((1, String::new()));
+    let calls = parse_calls(&first_line).map_err(|e| e.at_line(line_number))?;
+    if let Some((line_number, separator)) = input.next() {
+        expect_blank(&separator).map_err(|e| e.at_line(line_number))?;
+    }
 
     let mut boards: Vec<Board> = Vec::new();
 
-    while let Some(board) = parse_board(&mut *input) {
+    while let Some(board) = parse_board(&mut input)? {
         boards.push(build_board(board));
     }
 
-    (calls, boards)
+    Ok((calls, boards))
+}
+
+pub fn from_str(input: &str) -> Result<(Calls, Vec<Board>), ParseError> {
+    parse_input(&mut lines(input))
 }
 
-type Calls = Vec<u32>;
+pub type Calls = Vec<u32>;
 
-fn parse_calls(input: String) -> Calls {
-    input.split(',').map(|part| part.parse().unwrap()).collect()
+fn parse_calls(input: &str) -> Result<Calls, ParseError> {
+    parse_line(input)
 }
 
-fn parse_board(input: impl Iterator<Item = String>) -> Option<Vec<Vec<u32>>> {
+fn parse_board(
+    input: impl Iterator<Item = (usize, String)>,
+) -> Result<Option<Vec<Vec<u32>>>, ParseError> {
     let mut board: Vec<Vec<u32>> = Vec::new();
-    for line in input {
+    for (line_number, line) in input {
         if line.is_empty() {
             break;
-        } else {
-            board.push(
-                line.split_whitespace()
-                    .map(|column| column.parse().unwrap())
-                    .collect(),
-            );
         }
+
+        let row = line
+            .split_whitespace()
+            .map(|column| parse_field(&line, column, "a number"))
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|e| e.at_line(line_number))?;
+
+        if let Some(first_row) = board.first() {
+            if row.len() != first_row.len() {
+                let expected = format!("a row of {} numbers", first_row.len());
+                return Err(ParseError::new(1, line.trim(), &expected).at_line(line_number));
+            }
+        }
+
+        board.push(row);
     }
 
     if board.is_empty() {
-        None
+        Ok(None)
     } else {
-        Some(board)
+        Ok(Some(board))
     }
 }
 
-type Board = Vec<HashSet<u32>>;
+pub type Board = Vec<HashSet<u32>>;
 
 fn build_board(input: Vec<Vec<u32>>) -> Board {
     let rows: Vec<HashSet<u32>> = input
@@ -99,6 +124,26 @@ pub fn part2(calls: Calls, mut boards: Vec<Board>) -> Option<u32> {
     last_winner
 }
 
+pub struct Day04;
+
+impl Solver for Day04 {
+    type Input = (Calls, Vec<Board>);
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1((calls, boards): &Self::Input) -> Result<u32, SolveError> {
+        part1(calls.clone(), boards.clone()).ok_or_else(|| SolveError::no_answer("no board wins"))
+    }
+
+    fn part2((calls, boards): &Self::Input) -> Result<u32, SolveError> {
+        part2(calls.clone(), boards.clone()).ok_or_else(|| SolveError::no_answer("no board wins"))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -106,7 +151,15 @@ mod test {
 
     #[test]
     fn test_parse_calls() {
-        assert_eq!(vec![1, 2, 3, 4], parse_calls("1,2,3,4".to_string()))
+        assert_eq!(vec![1, 2, 3, 4], parse_calls("1,2,3,4").unwrap())
+    }
+
+    #[test]
+    fn test_parse_calls_error() {
+        assert_eq!(
+            ParseError::new(5, "", "a number"),
+            parse_calls("1,2,").unwrap_err()
+        )
     }
 
     #[test]
@@ -120,10 +173,45 @@ mod test {
 
     "
         .lines()
-        .map(|line| line.to_string());
+        .map(|line| line.to_string())
+        .enumerate();
 
         input.next();
-        assert_eq!(14, parse_board(input).unwrap()[2][2])
+        assert_eq!(14, parse_board(input).unwrap().unwrap()[2][2])
+    }
+
+    #[test]
+    fn test_parse_board_error() {
+        let input = vec!["1 2 3", "4 5", "7 8 9"]
+            .into_iter()
+            .map(|line| line.to_string())
+            .enumerate();
+
+        assert_eq!(
+            ParseError::new(1, "4 5", "a row of 3 numbers").at_line(1),
+            parse_board(input).unwrap_err()
+        )
+    }
+
+    #[test]
+    fn test_missing_separator() {
+        assert_eq!(
+            ParseError::new(1, "1 2 3", "a blank line").at_line(2),
+            from_str("1,2\n1 2 3\n4 5 6\n").unwrap_err()
+        )
+    }
+
+    #[test]
+    fn test_no_winner() {
+        let input = from_str("1,2\n\n1 3\n4 5\n").unwrap();
+        assert_eq!(
+            Err(SolveError::no_answer("no board wins")),
+            Day04::part1(&input)
+        );
+        assert_eq!(
+            Err(SolveError::no_answer("no board wins")),
+            Day04::part2(&input)
+        );
     }
 
     #[test]
@@ -137,7 +225,7 @@ mod test {
     fn example_day04_part1() {
         let mut lines = read_file("day04.example").unwrap();
 
-        let (calls, boards) = parse_input(&mut lines);
+        let (calls, boards) = parse_input(&mut lines).unwrap();
 
         let result = part1(calls, boards);
 
@@ -148,7 +236,7 @@ mod test {
     fn exec_day04_part1() {
         let mut lines = read_file("day04.txt").unwrap();
 
-        let (calls, boards) = parse_input(&mut lines);
+        let (calls, boards) = parse_input(&mut lines).unwrap();
 
         let result = part1(calls, boards).unwrap();
 
@@ -159,7 +247,7 @@ mod test {
     fn example_day04_part2() {
         let mut lines = read_file("day04.example").unwrap();
 
-        let (calls, boards) = parse_input(&mut lines);
+        let (calls, boards) = parse_input(&mut lines).unwrap();
 
         let result = part2(calls, boards);
 
@@ -170,7 +258,7 @@ mod test {
     fn exec_day04_part2() {
         let mut lines = read_file("day04.txt").unwrap();
 
-        let (calls, boards) = parse_input(&mut lines);
+        let (calls, boards) = parse_input(&mut lines).unwrap();
 
         let result = part2(calls, boards).unwrap();
 
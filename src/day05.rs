@@ -1,7 +1,9 @@
-use crate::util::read_lines;
+use crate::util::{lines, parse_field, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
 use std::collections::HashSet;
 
 type Coord = (u32, u32);
+type Segment = (Coord, Coord);
 
 fn range(start: u32, end: u32) -> Vec<u32> {
     if start <= end {
@@ -11,19 +13,37 @@ fn range(start: u32, end: u32) -> Vec<u32> {
     }
 }
 
-pub fn read_puzzle(filename: &str, with_diag: bool) -> impl Iterator<Item = Coord> {
-    let intervals = read_lines(filename, |line| {
-        let values: Vec<u32> = line
-            .split(" -> ")
-            .flat_map(|coord| coord.split(','))
-            .map(|value| value.parse().unwrap())
-            .collect();
+pub fn parse_segment(line: &str) -> Result<Segment, ParseError> {
+    let values = line
+        .split(" -> ")
+        .flat_map(|coord| coord.split(','))
+        .map(|value| parse_field(line, value, "a number"))
+        .collect::<Result<Vec<u32>, _>>()?;
 
-        Some(((values[0], values[1]), (values[2], values[3])))
-    })
-    .unwrap();
+    if values.len() != 4 {
+        return Err(ParseError::new(1, line, "a segment like `x1,y1 -> x2,y2`"));
+    }
+
+    Ok(((values[0], values[1]), (values[2], values[3])))
+}
+
+pub fn from_str(input: &str) -> Result<Vec<Segment>, ParseError> {
+    try_parse_lines(lines(input), parse_segment)
+}
 
-    intervals.flat_map(move |((sx, sy), (tx, ty))| -> Vec<Coord> {
+pub fn read_puzzle(
+    filename: &str,
+    with_diag: bool,
+) -> Result<impl Iterator<Item = Coord>, InputError> {
+    let segments = parse_file(filename, from_str)?;
+    Ok(expand(segments.into_iter(), with_diag))
+}
+
+pub fn expand(
+    segments: impl Iterator<Item = Segment>,
+    with_diag: bool,
+) -> impl Iterator<Item = Coord> {
+    segments.flat_map(move |((sx, sy), (tx, ty))| -> Vec<Coord> {
         if sx == tx {
             range(sy, ty).iter().map(|y| (sx, *y)).collect()
         } else if sy == ty {
@@ -54,33 +74,62 @@ pub fn find_danger(input: impl Iterator<Item = Coord>) -> u32 {
     count
 }
 
+pub struct Day05;
+
+impl Solver for Day05 {
+    type Input = Vec<Segment>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(find_danger(expand(input.iter().copied(), false)))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(find_danger(expand(input.iter().copied(), true)))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_parse_segment() {
+        assert_eq!(((0, 9), (5, 9)), parse_segment("0,9 -> 5,9").unwrap());
+        assert_eq!(
+            ParseError::new(8, "x", "a number"),
+            parse_segment("0,9 -> x,9").unwrap_err()
+        );
+    }
+
     #[test]
     fn example_day05_part1() {
-        assert_eq!(5, find_danger(read_puzzle("day05.example", false)));
+        assert_eq!(5, find_danger(read_puzzle("day05.example", false).unwrap()));
     }
 
     #[test]
     fn exec_day05_part1() {
         println!(
             "Day 05, Part 1: {}",
-            find_danger(read_puzzle("day05.txt", false))
+            find_danger(read_puzzle("day05.txt", false).unwrap())
         );
     }
 
     #[test]
     fn example_day05_part2() {
-        assert_eq!(12, find_danger(read_puzzle("day05.example", true)));
+        assert_eq!(12, find_danger(read_puzzle("day05.example", true).unwrap()));
     }
 
     #[test]
     fn exec_day05_part2() {
         println!(
             "Day 05, Part 2: {}",
-            find_danger(read_puzzle("day05.txt", true))
+            find_danger(read_puzzle("day05.txt", true).unwrap())
         );
     }
 }
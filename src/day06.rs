@@ -1,3 +1,6 @@
+use crate::util::{parse_line, ParseError};
+use crate::{SolveError, Solver};
+
 pub type Shoal = Vec<u32>;
 
 pub fn next_shoal_tick(input: Shoal) -> Shoal {
@@ -13,6 +16,10 @@ pub fn next_shoal_tick(input: Shoal) -> Shoal {
         .collect()
 }
 
+pub fn from_str(input: &str) -> Result<Shoal, ParseError> {
+    parse_line(input)
+}
+
 pub fn part1(input: Shoal, iterations: u32) -> usize {
     let mut shoal = input;
     for _ in 0..iterations {
@@ -41,6 +48,26 @@ pub fn part2(input: Shoal, iterations: u32) -> i64 {
     ocean.iter().sum()
 }
 
+pub struct Day06;
+
+impl Solver for Day06 {
+    type Input = Shoal;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part1(input.clone(), 80))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64, SolveError> {
+        Ok(part2(input.clone(), 256))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -60,7 +87,7 @@ mod test {
 
     #[test]
     fn exec_day06_part1() {
-        let input: Vec<u32> = read_line("day06.txt");
+        let input: Vec<u32> = read_line("day06.txt").unwrap();
 
         println!("Day 06 Part 1 - {}", part1(input, 80));
     }
@@ -74,7 +101,7 @@ mod test {
 
     #[test]
     fn exec_day06_part2() {
-        let input: Vec<u32> = read_line("day06.txt");
+        let input: Vec<u32> = read_line("day06.txt").unwrap();
 
         println!("Day 06 Part 2 - {}", part2(input, 256));
     }
@@ -1,5 +1,12 @@
+use crate::util::{parse_line, ParseError};
+use crate::{SolveError, Solver};
+
 type Crabs = Vec<i32>;
 
+pub fn from_str(input: &str) -> Result<Crabs, ParseError> {
+    parse_line(input)
+}
+
 pub fn part1(input: Crabs) -> i32 {
     let lowest = input.iter().min().unwrap();
     let highest = input.iter().max().unwrap();
@@ -29,6 +36,26 @@ pub fn part2(input: Crabs) -> i32 {
         .unwrap()
 }
 
+pub struct Day07;
+
+impl Solver for Day07 {
+    type Input = Crabs;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i32, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i32, SolveError> {
+        Ok(part2(input.clone()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -42,7 +69,7 @@ mod test {
 
     #[test]
     fn exec_day07_part1() {
-        let input: Crabs = read_line("day07.txt");
+        let input: Crabs = read_line("day07.txt").unwrap();
         println!("Day 07 Part 1: {}", part1(input));
     }
 
@@ -54,7 +81,7 @@ mod test {
 
     #[test]
     fn exec_day07_part2() {
-        let input: Crabs = read_line("day07.txt");
+        let input: Crabs = read_line("day07.txt").unwrap();
         println!("Day 07 Part 2: {}", part2(input));
     }
 }
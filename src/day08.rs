@@ -1,25 +1,48 @@
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::util::{column_of, lines, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
 use std::collections::HashSet;
 
 type Displays = Vec<String>;
 type Line = (Displays, Displays);
 
-fn read_displays(input: &str) -> Displays {
-    input
-        .split_whitespace()
-        .map(|component| component.to_string())
+// Reads the displays in `half`, a slice of `line`, so that bad segments can
+// be reported by their column in the line.
+fn read_displays(line: &str, half: &str) -> Result<Displays, ParseError> {
+    half.split_whitespace()
+        .map(|display| {
+            match display
+                .char_indices()
+                .find(|(_, c)| !('a'..='g').contains(c))
+            {
+                Some((i, c)) => Err(ParseError::new(
+                    column_of(line, display) + i,
+                    &c.to_string(),
+                    "a segment from `a` to `g`",
+                )),
+                None => Ok(display.to_string()),
+            }
+        })
         .collect()
 }
 
-pub fn read_puzzle(file: &str) -> impl Iterator<Item = Line> {
-    read_lines(file, |line| {
-        let mut halves = line.split(" | ");
-        let (signals_half, value_half) = (halves.next().unwrap(), halves.next().unwrap());
-        Some((read_displays(signals_half), read_displays(value_half)))
-    })
-    .unwrap()
+pub fn parse_line(line: &str) -> Result<Line, ParseError> {
+    let (signals_half, value_half) = line
+        .split_once(" | ")
+        .ok_or_else(|| ParseError::new(line.len() + 1, "", "` | ` before the output value"))?;
+    Ok((
+        read_displays(line, signals_half)?,
+        read_displays(line, value_half)?,
+    ))
+}
+
+pub fn from_str(input: &str) -> Result<Vec<Line>, ParseError> {
+    try_parse_lines(lines(input), parse_line)
+}
+
+pub fn read_puzzle(file: &str) -> Result<impl Iterator<Item = Line>, InputError> {
+    Ok(parse_file(file, from_str)?.into_iter())
 }
 
 pub fn part1(input: impl Iterator<Item = Line>) -> usize {
@@ -56,7 +79,9 @@ fn decode(key: &[char], cyphertext: &str) -> String {
         .collect()
 }
 
-fn solve((signals, value): Line) -> usize {
+/// The output value, or `None` if the signals do not pin down exactly one
+/// wiring, or the output is not made of digits under it.
+fn solve((signals, value): Line) -> Option<usize> {
     let solutions: Vec<Vec<char>> = ('a'..='g')
         .permutations(7)
         .filter(|candidate| {
@@ -66,52 +91,99 @@ fn solve((signals, value): Line) -> usize {
                 .all(|plaintext| VALID.contains(plaintext.as_str()))
         })
         .collect();
-    assert_eq!(solutions.len(), 1);
+    let key = match solutions.as_slice() {
+        [key] => key,
+        _ => return None,
+    };
     value
         .iter()
         .map(|display| {
-            let plaintext = decode(&solutions[0], display);
-            DIGITS.iter().position(|&digit| plaintext == digit).unwrap()
+            let plaintext = decode(key, display);
+            DIGITS.iter().position(|&digit| plaintext == digit)
         })
-        .fold(0, |acc, digit| acc * 10 + digit)
+        .fold_options(0, |acc, digit| acc * 10 + digit)
 }
 
-pub fn part2(input: impl Iterator<Item = Line>) -> usize {
+/// The sum of the output values, or `None` if any line cannot be decoded.
+pub fn part2(input: impl Iterator<Item = Line>) -> Option<usize> {
     input.map(solve).sum()
 }
 
+pub struct Day08;
+
+impl Solver for Day08 {
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part1(input.iter().cloned()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, SolveError> {
+        part2(input.iter().cloned())
+            .ok_or_else(|| SolveError::no_answer("a line's wiring cannot be worked out"))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_read_puzzle() {
-        let mut input = read_puzzle("day08.example");
+        let mut input = read_puzzle("day08.example").unwrap();
         let (_, value) = input.next().unwrap();
         assert_eq!(value, vec!["fdgacbe", "cefdb", "cefbgd", "gcbe"])
     }
 
+    #[test]
+    fn test_parse_line_error() {
+        assert_eq!(
+            ParseError::new(12, "", "` | ` before the output value"),
+            parse_line("be cfbegad ").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(9, "x", "a segment from `a` to `g`"),
+            parse_line("ab cd | xy").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(5, "h", "a segment from `a` to `g`"),
+            parse_line("ab ch | ab").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_undecodable() {
+        let input = from_str("ab cd | ef").unwrap();
+        assert_eq!(None, part2(input.into_iter()));
+    }
+
     #[test]
     fn example_day08_part1() {
-        let input = read_puzzle("day08.example");
+        let input = read_puzzle("day08.example").unwrap();
         assert_eq!(26, part1(input));
     }
 
     #[test]
     fn exec_day08_part1() {
-        let input = read_puzzle("day08.txt");
+        let input = read_puzzle("day08.txt").unwrap();
         println!("Day 08, Part 1 – {}", part1(input));
     }
 
     #[test]
     fn example_day08_part2() {
-        let input = read_puzzle("day08.example");
-        assert_eq!(61229, part2(input));
+        let input = read_puzzle("day08.example").unwrap();
+        assert_eq!(Some(61229), part2(input));
     }
 
     #[test]
     fn exec_day08_part2() {
-        let input = read_puzzle("day08.txt");
-        println!("Day 08, Part 2 – {}", part2(input));
+        let input = read_puzzle("day08.txt").unwrap();
+        println!("Day 08, Part 2 – {}", part2(input).unwrap());
     }
 }
@@ -2,60 +2,39 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::util::grid::{parse_digits, Grid, Point};
+use crate::util::{parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
 
-type Puzzle = Vec<Vec<u32>>;
+type Puzzle = Grid<u32>;
 
-pub fn parse_puzzle(file: &str) -> Puzzle {
-    read_lines(file, |line: String| -> Option<Vec<u32>> {
-        Some(line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-    })
-    .unwrap()
-    .collect()
-}
-
-fn get(input: &[Vec<u32>], x: Option<usize>, y: Option<usize>) -> Option<u32> {
-    let line = input.get(y?)?;
-    let cell = line.get(x?)?;
-    Some(*cell)
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    parse_digits(input)
 }
 
-fn add1(input: Option<usize>) -> Option<usize> {
-    input.and_then(|n| n.checked_add(1))
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
 }
 
-fn minus1(input: Option<usize>) -> Option<usize> {
-    input.and_then(|n| n.checked_sub(1))
+fn low_points(input: &Puzzle) -> impl Iterator<Item = Point> + '_ {
+    input.points().filter(move |&point| {
+        input
+            .neighbours4(point)
+            .all(|neighbour| input[point] < input[neighbour])
+    })
 }
 
 pub fn part1(input: Puzzle) -> u32 {
-    let mut risk = 0;
-    for y in (0..input.len()).map(Some) {
-        for x in (0..input[0].len()).map(Some) {
-            let focus = get(&input, x, y).unwrap();
-            let l = get(&input, minus1(x), y).unwrap_or(u32::MAX);
-            let r = get(&input, add1(x), y).unwrap_or(u32::MAX);
-            let u = get(&input, x, minus1(y)).unwrap_or(u32::MAX);
-            let d = get(&input, x, add1(y)).unwrap_or(u32::MAX);
-            if focus < l && focus < r && focus < u && focus < d {
-                risk += focus + 1;
-            }
-        }
-    }
-    risk
+    low_points(&input).map(|point| input[point] + 1).sum()
 }
 
-pub fn explore(input: &[Vec<u32>], x: Option<usize>, y: Option<usize>) -> u32 {
+pub fn explore(input: &Puzzle, start: Point) -> u32 {
     let mut count = 0;
-    let mut seen: HashSet<(Option<usize>, Option<usize>)> = HashSet::new();
-    let mut stack = vec![(x, y)];
-    while let Some((x, y)) = stack.pop() {
-        if !seen.contains(&(x, y)) && get(input, x, y).unwrap_or(9) != 9 {
-            stack.push((minus1(x), y));
-            stack.push((add1(x), y));
-            stack.push((x, minus1(y)));
-            stack.push((x, add1(y)));
-            seen.insert((x, y));
+    let mut seen: HashSet<Point> = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(point) = stack.pop() {
+        if input[point] != 9 && seen.insert(point) {
+            stack.extend(input.neighbours4(point));
             count += 1
         }
     }
@@ -63,20 +42,32 @@ pub fn explore(input: &[Vec<u32>], x: Option<usize>, y: Option<usize>) -> u32 {
 }
 
 pub fn part2(input: Puzzle) -> u32 {
-    let mut solutions = vec![];
-    for y in (0..input.len()).map(Some) {
-        for x in (0..input[0].len()).map(Some) {
-            let focus = get(&input, x, y).unwrap();
-            let l = get(&input, minus1(x), y).unwrap_or(u32::MAX);
-            let r = get(&input, add1(x), y).unwrap_or(u32::MAX);
-            let u = get(&input, x, minus1(y)).unwrap_or(u32::MAX);
-            let d = get(&input, x, add1(y)).unwrap_or(u32::MAX);
-            if focus < l && focus < r && focus < u && focus < d {
-                solutions.push(explore(&input, x, y));
-            }
-        }
+    low_points(&input)
+        .map(|point| explore(&input, point))
+        .sorted()
+        .rev()
+        .take(3)
+        .product()
+}
+
+pub struct Day09;
+
+impl Solver for Day09 {
+    type Input = Puzzle;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part2(input.clone()))
     }
-    solutions.iter().sorted().rev().take(3).product()
 }
 
 #[cfg(test)]
@@ -85,32 +76,40 @@ mod test {
 
     #[test]
     fn test_parse_puzzle() {
-        let input = parse_puzzle("day09.example");
-        assert_eq!(5, input.len());
-        assert_eq!(10, input[0].len());
+        let input = parse_puzzle("day09.example").unwrap();
+        assert_eq!(5, input.height());
+        assert_eq!(10, input.width());
+    }
+
+    #[test]
+    fn test_parse_puzzle_error() {
+        assert_eq!(
+            ParseError::new(3, "?", "a digit"),
+            from_str("21?9").unwrap_err()
+        );
     }
 
     #[test]
     fn example_day09_part1() {
-        let input = parse_puzzle("day09.example");
+        let input = parse_puzzle("day09.example").unwrap();
         assert_eq!(15, part1(input));
     }
 
     #[test]
     fn exec_day09_part1() {
-        let input = parse_puzzle("day09.txt");
+        let input = parse_puzzle("day09.txt").unwrap();
         println!("Day 09 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example_day09_part2() {
-        let input = parse_puzzle("day09.example");
+        let input = parse_puzzle("day09.example").unwrap();
         assert_eq!(1134, part2(input));
     }
 
     #[test]
     fn exec_day09_part2() {
-        let input = parse_puzzle("day09.txt");
+        let input = parse_puzzle("day09.txt").unwrap();
         println!("Day 09 Part 2 - {}", part2(input));
     }
 }
@@ -1,12 +1,23 @@
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::util::{lines, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
 
-pub fn parse_puzzle(file: &str) -> impl Iterator<Item = Vec<char>> {
-    read_lines(file, |line: String| -> Option<Vec<char>> {
-        Some(line.chars().collect())
-    })
-    .unwrap()
+pub fn parse_line(line: &str) -> Result<Vec<char>, ParseError> {
+    line.char_indices()
+        .map(|(i, c)| match c {
+            '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' => Ok(c),
+            _ => Err(ParseError::new(i + 1, &c.to_string(), "a bracket")),
+        })
+        .collect()
+}
+
+pub fn from_str(input: &str) -> Result<Vec<Vec<char>>, ParseError> {
+    try_parse_lines(lines(input), parse_line)
+}
+
+pub fn parse_puzzle(file: &str) -> Result<impl Iterator<Item = Vec<char>>, InputError> {
+    Ok(parse_file(file, from_str)?.into_iter())
 }
 
 pub fn score(line: Vec<char>) -> u64 {
@@ -64,9 +75,30 @@ pub fn fix(line: Vec<char>) -> u64 {
     })
 }
 
-pub fn part2(input: impl Iterator<Item = Vec<char>>) -> u64 {
+/// The middle completion score, or `None` if no line is incomplete.
+pub fn part2(input: impl Iterator<Item = Vec<char>>) -> Option<u64> {
     let scores: Vec<u64> = input.map(fix).filter(|&n| n != 0).sorted().collect();
-    scores[scores.len() / 2]
+    scores.get(scores.len() / 2).copied()
+}
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Vec<Vec<char>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64, SolveError> {
+        Ok(part1(input.iter().cloned()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, SolveError> {
+        part2(input.iter().cloned()).ok_or_else(|| SolveError::no_answer("no line is incomplete"))
+    }
 }
 
 #[cfg(test)]
@@ -75,38 +107,39 @@ mod test {
 
     #[test]
     fn test_parse_puzzle() {
-        let mut input = parse_puzzle("day10.example");
+        let mut input = parse_puzzle("day10.example").unwrap();
         assert_eq!(&'>', input.next().unwrap().last().unwrap());
     }
 
     #[test]
     fn example_day10_part1() {
-        let input = parse_puzzle("day10.example");
+        let input = parse_puzzle("day10.example").unwrap();
         assert_eq!(26397, part1(input));
     }
 
     #[test]
     fn exec_day10_part1() {
-        let input = parse_puzzle("day10.txt");
+        let input = parse_puzzle("day10.txt").unwrap();
         println!("Day 10 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example_day10_fix() {
-        let input = parse_puzzle("day10.example");
+        let input = parse_puzzle("day10.example").unwrap();
         let fixes: Vec<u64> = input.map(fix).filter(|&n| n > 0).collect();
         assert_eq!(vec![288957, 5566, 1480781, 995444, 294], fixes);
     }
 
     #[test]
     fn example_day10_part2() {
-        let input = parse_puzzle("day10.example");
-        assert_eq!(288957, part2(input));
+        let input = parse_puzzle("day10.example").unwrap();
+        assert_eq!(Some(288957), part2(input));
+        assert_eq!(None, part2(from_str("()\n(]").unwrap().into_iter()));
     }
 
     #[test]
     fn exec_day10_part2() {
-        let input = parse_puzzle("day10.txt");
-        println!("Day 10 Part 2 - {}", part2(input));
+        let input = parse_puzzle("day10.txt").unwrap();
+        println!("Day 10 Part 2 - {}", part2(input).unwrap());
     }
 }
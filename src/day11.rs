@@ -1,116 +1,337 @@
-use crate::util::read_lines;
+use std::collections::HashSet;
+use std::fs::{create_dir_all, File};
+use std::io::{self, Write};
+use std::path::Path;
 
-type Puzzle = Vec<Vec<u32>>;
+use crate::util::grid::{parse_digits, Grid, Point};
+use crate::util::{parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
 
-pub fn parse_puzzle(file: &str) -> Puzzle {
-    read_lines(file, |line: String| -> Option<Vec<u32>> {
-        Some(line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-    })
-    .unwrap()
-    .collect()
+type Puzzle = Grid<u32>;
+
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    parse_digits(input)
 }
 
-fn update_and_get(
-    input: &mut Puzzle,
-    x: Option<usize>,
-    y: Option<usize>,
-    f: fn(u32) -> u32,
-) -> Option<u32> {
-    let line = input.get_mut(y?)?;
-    let cell = line.get_mut(x?)?;
-    *cell = f(*cell);
-    Some(*cell)
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
 }
 
-fn add1(input: Option<usize>) -> Option<usize> {
-    input.and_then(|n| n.checked_add(1))
+/// The energy an octopus can hold before it flashes in the puzzle.
+pub const THRESHOLD: u32 = 9;
+
+/// Advances `input` by one step, flashing every octopus whose energy goes
+/// over `threshold`. Returns the octopuses that flashed, in the order they
+/// did.
+pub fn step_in_order(input: &mut Puzzle, threshold: u32) -> Vec<Point> {
+    let mut flashed = vec![];
+    let mut seen = HashSet::new();
+
+    let mut stack = vec![];
+    for point in input.points() {
+        input[point] += 1;
+        if input[point] > threshold && seen.insert(point) {
+            stack.push(point);
+        }
+    }
+
+    while let Some(point) = stack.pop() {
+        flashed.push(point);
+        for neighbour in input.neighbours8(point) {
+            input[neighbour] += 1;
+            if input[neighbour] > threshold && seen.insert(neighbour) {
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    for cell in input.iter_mut() {
+        if *cell > threshold {
+            *cell = 0;
+        }
+    }
+
+    flashed
 }
 
-fn minus1(input: Option<usize>) -> Option<usize> {
-    input.and_then(|n| n.checked_sub(1))
+/// Like [`step_in_order`], but only counts the flashes.
+pub fn step(input: &mut Puzzle, threshold: u32) -> u32 {
+    step_in_order(input, threshold).len() as u32
 }
 
-fn step(input: &mut Puzzle) -> u32 {
-    let mut flashes = 0;
+/// The grid after one step, and which octopuses flashed during it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Step {
+    /// Counting from 1.
+    pub number: usize,
+    pub flashes: u32,
+    /// In the order they flashed.
+    pub flashed: Vec<Point>,
+    pub grid: Puzzle,
+}
 
-    let mut stack: Vec<(Option<usize>, Option<usize>)> = vec![];
-    for x in (0..10).map(Some) {
-        for y in (0..10).map(Some) {
-            let cell = update_and_get(input, x, y, |n| n + 1).unwrap();
-            assert!(cell <= 10);
-            if cell == 10 {
-                stack.push((x, y));
-            }
+/// Runs the simulation forever, one [`Step`] at a time.
+pub struct Steps {
+    grid: Puzzle,
+    threshold: u32,
+    number: usize,
+}
+
+impl Steps {
+    pub fn new(grid: Puzzle) -> Steps {
+        Steps::with_threshold(grid, THRESHOLD)
+    }
+
+    pub fn with_threshold(grid: Puzzle, threshold: u32) -> Steps {
+        Steps {
+            grid,
+            threshold,
+            number: 0,
         }
     }
+}
 
-    while let Some((x, y)) = stack.pop() {
-        flashes += 1;
-        [
-            (minus1(x), minus1(y)),
-            ((x), minus1(y)),
-            (add1(x), minus1(y)),
-            (minus1(x), y),
-            (add1(x), y),
-            (minus1(x), add1(y)),
-            ((x), add1(y)),
-            (add1(x), add1(y)),
-        ]
-        .iter()
-        .for_each(|(x, y)| {
-            if let Some(cell) = update_and_get(input, *x, *y, |n| n + 1) {
-                if cell == 10 {
-                    stack.push((*x, *y));
-                }
-            }
-        });
+impl Iterator for Steps {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        let flashed = step_in_order(&mut self.grid, self.threshold);
+        self.number += 1;
+        Some(Step {
+            number: self.number,
+            flashes: flashed.len() as u32,
+            flashed,
+            grid: self.grid.clone(),
+        })
     }
+}
 
-    for x in (0..10).map(Some) {
-        for y in (0..10).map(Some) {
-            update_and_get(input, x, y, |n| if n > 9 { 0 } else { n });
+/// Draws a step with each octopus's energy as a digit, or `*` if it just
+/// flashed.
+pub fn render_step(step: &Step) -> String {
+    let flashed: HashSet<&Point> = step.flashed.iter().collect();
+    let grid = Grid::from_fn(step.grid.width(), step.grid.height(), |point| {
+        if flashed.contains(&point) {
+            '*'
+        } else {
+            char::from_digit(step.grid[point], 36).unwrap_or('#')
+        }
+    });
+    format!(
+        "After step {}, {} flashed:\n{}",
+        step.number,
+        step.flashes,
+        grid.render(|c| *c)
+    )
+}
+
+/// Draws a step as a binary PPM image, `scale` pixels to an octopus. Those
+/// that just flashed are yellow; the rest get brighter as their energy nears
+/// `threshold`.
+pub fn to_ppm(step: &Step, threshold: u32, scale: usize) -> Vec<u8> {
+    let flashed: HashSet<&Point> = step.flashed.iter().collect();
+    let (width, height) = (step.grid.width() * scale, step.grid.height() * scale);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for y in 0..height {
+        for x in 0..width {
+            let point = (x / scale, y / scale);
+            if flashed.contains(&point) {
+                image.extend([255, 255, 0]);
+            } else {
+                let level = (step.grid[point].min(threshold) * 200 / threshold.max(1)) as u8;
+                image.extend([level, level, level]);
+            }
         }
     }
+    image
+}
+
+/// Writes each of `steps` to `dir` as `step0001.ppm` and so on, returning
+/// how many frames were written.
+pub fn write_frames(
+    steps: impl Iterator<Item = Step>,
+    threshold: u32,
+    dir: &Path,
+) -> io::Result<usize> {
+    create_dir_all(dir)?;
+    let mut frames = 0;
+    for step in steps {
+        let path = dir.join(format!("step{:04}.ppm", step.number));
+        File::create(path)?.write_all(&to_ppm(&step, threshold, 8))?;
+        frames += 1;
+    }
+    Ok(frames)
+}
 
-    flashes
+/// The first step, counting from 1, on which every octopus flashes at once,
+/// if it happens within `limit` steps.
+pub fn first_synchronised(steps: Steps, limit: usize) -> Option<usize> {
+    let size = (steps.grid.width() * steps.grid.height()) as u32;
+    steps
+        .take(limit)
+        .position(|step| step.flashes == size)
+        .map(|index| index + 1)
 }
 
 pub fn part1(input: &mut Puzzle) -> u32 {
-    (0..100).map(|_| step(input)).sum()
+    (0..100).map(|_| step(input, THRESHOLD)).sum()
 }
 
-pub fn part2(input: &mut Puzzle) -> u32 {
-    1 + (0..1000)
-        .map(|_| step(input))
-        .take_while(|n| *n != 100)
-        .count() as u32
+/// How many steps to try in [`part2`] before giving up.
+pub const SYNCHRONISE_LIMIT: usize = 10_000;
+
+/// The first step on which every octopus flashes, or `None` if that does not
+/// happen within [`SYNCHRONISE_LIMIT`] steps.
+pub fn part2(input: &mut Puzzle) -> Option<u32> {
+    first_synchronised(Steps::new(input.clone()), SYNCHRONISE_LIMIT).map(|step| step as u32)
+}
+
+pub struct Day11;
+
+impl Solver for Day11 {
+    type Input = Puzzle;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part1(&mut input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32, SolveError> {
+        part2(&mut input.clone()).ok_or_else(|| {
+            SolveError::NoAnswer(format!(
+                "the octopuses do not all flash at once within {} steps",
+                SYNCHRONISE_LIMIT
+            ))
+        })
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
     use super::*;
 
+    #[test]
+    fn test_steps() {
+        let input = from_str("11111\n19991\n19191\n19991\n11111").unwrap();
+        let steps: Vec<Step> = Steps::new(input).take(2).collect();
+        assert_eq!(9, steps[0].flashes);
+        assert_eq!(
+            from_str("34543\n40004\n50005\n40004\n34543").unwrap(),
+            steps[0].grid
+        );
+        assert_eq!(0, steps[1].flashes);
+        assert_eq!(
+            from_str("45654\n51115\n61116\n51115\n45654").unwrap(),
+            steps[1].grid
+        );
+    }
+
+    #[test]
+    fn test_flash_order() {
+        let input = from_str("000\n080\n009").unwrap();
+        let step = Steps::new(input).next().unwrap();
+        assert_eq!(1, step.number);
+        assert_eq!(vec![(2, 2), (1, 1)], step.flashed);
+        assert_eq!(
+            "After step 1, 2 flashed:\n222\n2*3\n23*",
+            render_step(&step)
+        );
+    }
+
+    #[test]
+    fn test_above_threshold() {
+        let mut input = from_str("5\n0").unwrap();
+        assert_eq!(vec![(0, 0)], step_in_order(&mut input, 2));
+        assert_eq!(from_str("0\n2").unwrap(), input);
+
+        let mut input = from_str("57\n00").unwrap();
+        assert_eq!(4, step(&mut input, 2));
+        assert_eq!(from_str("00\n00").unwrap(), input);
+    }
+
+    #[test]
+    fn test_write_frames() {
+        let input = from_str("09\n00").unwrap();
+        let step = Steps::new(input.clone()).next().unwrap();
+        let image = to_ppm(&step, THRESHOLD, 2);
+        let header = b"P6\n4 4\n255\n";
+        assert!(image.starts_with(header));
+        let pixels = &image[header.len()..];
+        assert_eq!(4 * 4 * 3, pixels.len());
+        assert_eq!([255, 255, 0], pixels[2 * 3..3 * 3]);
+        assert_eq!([44, 44, 44], pixels[0..3]);
+
+        // Unique to this run, so that concurrent runs do not share frames.
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "advent2021-day11-frames-{}-{}",
+            std::process::id(),
+            nanos
+        ));
+        assert_eq!(
+            3,
+            write_frames(Steps::new(input).take(3), THRESHOLD, &dir).unwrap()
+        );
+        assert!(dir.join("step0003.ppm").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_first_synchronised() {
+        let input = from_str("000\n000").unwrap();
+        assert_eq!(Some(10), first_synchronised(Steps::new(input.clone()), 100));
+        assert_eq!(
+            Some(3),
+            first_synchronised(Steps::with_threshold(input, 2), 100)
+        );
+        let input = from_str("09\n00\n00").unwrap();
+        assert_eq!(None, first_synchronised(Steps::new(input), 5));
+    }
+
+    #[test]
+    fn test_never_synchronised() {
+        // They take turns to flash, and each flash keeps the other behind.
+        let input = from_str("05").unwrap();
+        assert_eq!(None, part2(&mut input.clone()));
+        assert_eq!(
+            Err(SolveError::NoAnswer(
+                "the octopuses do not all flash at once within 10000 steps".to_string()
+            )),
+            Day11::part2(&input)
+        );
+    }
+
     #[test]
     fn example_day11_part1() {
-        let mut input = parse_puzzle("day11.example");
+        let mut input = parse_puzzle("day11.example").unwrap();
         assert_eq!(1656, part1(&mut input));
     }
 
     #[test]
     fn exec_day11_part1() {
-        let mut input = parse_puzzle("day11.txt");
+        let mut input = parse_puzzle("day11.txt").unwrap();
         println!("Day 11 Part 1 - {}", part1(&mut input));
     }
 
     #[test]
     fn example_day11_part2() {
-        let mut input = parse_puzzle("day11.example");
-        assert_eq!(195, part2(&mut input));
+        let mut input = parse_puzzle("day11.example").unwrap();
+        assert_eq!(Some(195), part2(&mut input));
     }
 
     #[test]
     fn exec_day11_part2() {
-        let mut input = parse_puzzle("day11.txt");
-        println!("Day 11 Part 2 - {}", part2(&mut input));
+        let mut input = parse_puzzle("day11.txt").unwrap();
+        println!("Day 11 Part 2 - {}", part2(&mut input).unwrap());
     }
 }
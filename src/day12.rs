@@ -1,21 +1,36 @@
 use itertools::Itertools;
 use multimap::MultiMap;
 
-use crate::util::read_lines;
+use crate::util::{lines, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
 
 type Puzzle = MultiMap<String, String>;
 
-pub fn read_puzzle(file: &str) -> Puzzle {
-    read_lines(file, |line| {
-        let mut components = line.split('-');
-        Some((
-            components.next()?.to_string(),
-            components.next()?.to_string(),
-        ))
-    })
-    .unwrap()
-    .flat_map(|(src, tgt)| vec![(src.clone(), tgt.clone()), (tgt, src)])
-    .collect()
+type Edge = (String, String);
+
+pub fn parse_edge(line: &str) -> Result<Edge, ParseError> {
+    match line.split_once('-') {
+        Some((src, tgt)) if !src.is_empty() && !tgt.is_empty() => {
+            Ok((src.to_string(), tgt.to_string()))
+        }
+        _ => Err(ParseError::new(1, line, "an edge like `start-A`")),
+    }
+}
+
+pub fn build_puzzle(edges: impl Iterator<Item = Edge>) -> Puzzle {
+    edges
+        .flat_map(|(src, tgt)| vec![(src.clone(), tgt.clone()), (tgt, src)])
+        .collect()
+}
+
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    Ok(build_puzzle(
+        try_parse_lines(lines(input), parse_edge)?.into_iter(),
+    ))
+}
+
+pub fn read_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
 }
 
 pub fn is_big(cave: &str) -> bool {
@@ -52,7 +67,7 @@ pub fn part2(input: Puzzle) -> u32 {
         if end != "end" {
             let used_small = path
                 .iter()
-                .filter(|&&cave| !is_big(&cave.to_string()))
+                .filter(|&&cave| !is_big(cave))
                 .duplicates()
                 .count()
                 > 0;
@@ -73,67 +88,105 @@ pub fn part2(input: Puzzle) -> u32 {
     count
 }
 
+pub struct Day12;
+
+impl Solver for Day12 {
+    type Input = Puzzle;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part2(input.clone()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_read_puzzle() {
-        let input = read_puzzle("day12.example1");
+        let input = read_puzzle("day12.example1").unwrap();
         assert_eq!(2, input.get_vec("start").unwrap().len());
     }
 
+    #[test]
+    fn test_from_str() {
+        let input = from_str("start-A\nA-b\nA-end\n").unwrap();
+        assert_eq!(2, part1(input.clone()));
+        assert_eq!(
+            ParseError::new(1, "A", "an edge like `start-A`").at_line(2),
+            from_str("start-A\nA\n").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_edge_error() {
+        assert_eq!(
+            ParseError::new(1, "start", "an edge like `start-A`"),
+            parse_edge("start").unwrap_err()
+        );
+    }
+
     #[test]
     fn test_is_big() {
-        assert!(is_big(&"ABC".to_string()));
-        assert!(!is_big(&"abc".to_string()));
+        assert!(is_big("ABC"));
+        assert!(!is_big("abc"));
     }
 
     #[test]
     fn example1_day12_part1() {
-        let input = read_puzzle("day12.example1");
+        let input = read_puzzle("day12.example1").unwrap();
         assert_eq!(10, part1(input));
     }
 
     #[test]
     fn example2_day12_part1() {
-        let input = read_puzzle("day12.example2");
+        let input = read_puzzle("day12.example2").unwrap();
         assert_eq!(19, part1(input));
     }
 
     #[test]
     fn example3_day12_part1() {
-        let input = read_puzzle("day12.example3");
+        let input = read_puzzle("day12.example3").unwrap();
         assert_eq!(226, part1(input));
     }
 
     #[test]
     fn exec_day12_part1() {
-        let input = read_puzzle("day12.txt");
+        let input = read_puzzle("day12.txt").unwrap();
         println!("Day 12 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example1_day12_part2() {
-        let input = read_puzzle("day12.example1");
+        let input = read_puzzle("day12.example1").unwrap();
         assert_eq!(36, part2(input));
     }
 
     #[test]
     fn example2_day12_part2() {
-        let input = read_puzzle("day12.example2");
+        let input = read_puzzle("day12.example2").unwrap();
         assert_eq!(103, part2(input));
     }
 
     #[test]
     fn example3_day12_part2() {
-        let input = read_puzzle("day12.example3");
+        let input = read_puzzle("day12.example3").unwrap();
         assert_eq!(3509, part2(input));
     }
 
     #[test]
     fn exec_day12_part2() {
-        let input = read_puzzle("day12.txt");
+        let input = read_puzzle("day12.txt").unwrap();
         println!("Day 12 Part 2 - {}", part2(input));
     }
 }
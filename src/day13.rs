@@ -1,18 +1,33 @@
 use std::collections::HashSet;
+use std::str::FromStr;
 
-use crate::util::read_file;
+use crate::util::{lines, parse_field, parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
 use itertools::Itertools;
 use regex::Regex;
 
 type Coord = (i32, i32);
 type Fold = (char, i32);
 
+#[derive(Clone, Debug)]
 pub struct Puzzle {
     coords: Vec<Coord>,
     folds: Vec<Fold>,
 }
 
-pub fn parse_puzzle(file: &str) -> Puzzle {
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
+}
+
+impl FromStr for Puzzle {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+        from_str(input)
+    }
+}
+
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
     let coord_matcher = Regex::new(r"^([0-9]+),([0-9]+)$").unwrap();
     let fold_matcher = Regex::new(r"^fold along ([xy])=([0-9]+)$").unwrap();
 
@@ -20,22 +35,30 @@ pub fn parse_puzzle(file: &str) -> Puzzle {
     let mut coords: Vec<Coord> = vec![];
     let mut folds: Vec<Fold> = vec![];
 
-    for line in read_file(file).unwrap() {
+    for (index, line) in lines(input).enumerate() {
+        let at_line = |e: ParseError| e.at_line(index + 1);
         if line.is_empty() {
             on_folds = true;
         } else if !on_folds {
-            let captures = coord_matcher.captures(line.as_str()).unwrap();
-            coords.push((captures[1].parse().unwrap(), captures[2].parse().unwrap()))
+            let captures = coord_matcher
+                .captures(line.as_str())
+                .ok_or_else(|| at_line(ParseError::new(1, &line, "a coordinate like `x,y`")))?;
+            coords.push((
+                parse_field(&line, &captures[1], "a number").map_err(at_line)?,
+                parse_field(&line, &captures[2], "a number").map_err(at_line)?,
+            ))
         } else {
-            let captures = fold_matcher.captures(line.as_str()).unwrap();
+            let captures = fold_matcher.captures(line.as_str()).ok_or_else(|| {
+                at_line(ParseError::new(1, &line, "a fold like `fold along x=5`"))
+            })?;
             folds.push((
                 captures[1].chars().next().unwrap(),
-                captures[2].parse().unwrap(),
+                parse_field(&line, &captures[2], "a number").map_err(at_line)?,
             ))
         }
     }
 
-    Puzzle { coords, folds }
+    Ok(Puzzle { coords, folds })
 }
 
 pub fn part1(input: Puzzle) -> usize {
@@ -66,13 +89,12 @@ pub fn part2(input: Puzzle) -> Vec<Coord> {
     })
 }
 
-pub fn print_puzzle(input: Vec<Coord>) {
+pub fn render_puzzle(input: Vec<Coord>) -> String {
     let input_set: HashSet<Coord> = input.iter().copied().collect();
     let max_x = input.iter().map(|c| c.0).max().unwrap();
     let max_y = input.iter().map(|c| c.1).max().unwrap();
-    for y in 0..=max_y {
-        println!(
-            "{}",
+    (0..=max_y)
+        .map(|y| {
             (0..=max_x)
                 .map(|x| {
                     if input_set.contains(&(x, y)) {
@@ -82,7 +104,31 @@ pub fn print_puzzle(input: Vec<Coord>) {
                     }
                 })
                 .join("")
-        );
+        })
+        .join("\n")
+}
+
+pub fn print_puzzle(input: Vec<Coord>) {
+    println!("{}", render_puzzle(input));
+}
+
+pub struct Day13;
+
+impl Solver for Day13 {
+    type Input = Puzzle;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<String, SolveError> {
+        Ok(render_puzzle(part2(input.clone())))
     }
 }
 
@@ -92,32 +138,43 @@ mod test {
 
     #[test]
     fn test_parse_puzzle() {
-        let input = parse_puzzle("day13.example");
+        let input = parse_puzzle("day13.example").unwrap();
         assert_eq!(18, input.coords.len());
         assert_eq!(2, input.folds.len());
     }
 
+    #[test]
+    fn test_parse_puzzle_error() {
+        let error = "6,10\n0,14\n\nfold along z=7"
+            .parse::<Puzzle>()
+            .unwrap_err();
+        assert_eq!(
+            ParseError::new(1, "fold along z=7", "a fold like `fold along x=5`").at_line(4),
+            error
+        );
+    }
+
     #[test]
     fn example_day13_part1() {
-        let input = parse_puzzle("day13.example");
+        let input = parse_puzzle("day13.example").unwrap();
         assert_eq!(17, part1(input));
     }
 
     #[test]
     fn exec_day13_part1() {
-        let input = parse_puzzle("day13.txt");
+        let input = parse_puzzle("day13.txt").unwrap();
         println!("Day 13 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example_day13_part2() {
-        let input = parse_puzzle("day13.example");
+        let input = parse_puzzle("day13.example").unwrap();
         print_puzzle(part2(input));
     }
 
     #[test]
     fn exec_day13_part2() {
-        let input = parse_puzzle("day13.txt");
+        let input = parse_puzzle("day13.txt").unwrap();
         print_puzzle(part2(input));
     }
 }
@@ -1,34 +1,63 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
 use itertools::{Itertools, MinMaxResult};
 
-use crate::util::read_file;
+use crate::util::{expect_blank, lines, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
 
 type Bigram = (char, char);
 type Substititions = HashMap<Bigram, char>;
 
+#[derive(Clone, Debug)]
 pub struct Puzzle {
     template: String,
     substitutions: Substititions,
 }
 
-pub fn parse_puzzle(file: &str) -> Puzzle {
-    let mut lines = read_file(file).unwrap();
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
+}
+
+impl FromStr for Puzzle {
+    type Err = ParseError;
 
-    let template = lines.next().unwrap();
+    fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+        from_str(input)
+    }
+}
 
-    lines.next();
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    let mut lines = lines(input);
+    let template = lines.next().unwrap_or_default();
+    if template.is_empty() {
+        return Err(ParseError::new(1, "", "a polymer template"));
+    }
 
-    let substitutions: Substititions = lines
-        .map(|line| {
-            let chars: Vec<char> = line.chars().collect();
-            ((chars[0], chars[1]), chars[6])
-        })
+    if let Some(separator) = lines.next() {
+        expect_blank(&separator).map_err(|e| e.at_line(2))?;
+    }
+
+    // Rules start on the third line.
+    let substitutions: Substititions = try_parse_lines(lines, parse_rule)
+        .map_err(|e| {
+            let line = e.line + 2;
+            e.at_line(line)
+        })?
+        .into_iter()
         .collect();
 
-    Puzzle {
+    Ok(Puzzle {
         template,
         substitutions,
+    })
+}
+
+fn parse_rule(line: &str) -> Result<(Bigram, char), ParseError> {
+    let chars: Vec<char> = line.chars().collect();
+    match chars.as_slice() {
+        [a, b, ' ', '-', '>', ' ', c] => Ok(((*a, *b), *c)),
+        _ => Err(ParseError::new(1, line, "a rule like `AB -> C`")),
     }
 }
 
@@ -97,46 +126,79 @@ pub fn part2(input: &Puzzle, steps: u32) -> usize {
     }
 }
 
+pub struct Day14;
+
+impl Solver for Day14 {
+    type Input = Puzzle;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part2(input, 40))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_parser() {
-        let puzzle = parse_puzzle("day14.example");
+        let puzzle = parse_puzzle("day14.example").unwrap();
         assert_eq!(4, puzzle.template.len());
         assert_eq!(16, puzzle.substitutions.len());
         assert_eq!('B', puzzle.substitutions[&('C', 'H')])
     }
 
+    #[test]
+    fn test_parser_error() {
+        let error = "NNCB\n\nCH -> B\nHH => N".parse::<Puzzle>().unwrap_err();
+        assert_eq!(
+            ParseError::new(1, "HH => N", "a rule like `AB -> C`").at_line(4),
+            error
+        );
+        assert_eq!(
+            ParseError::new(1, "CH -> B", "a blank line").at_line(2),
+            from_str("NNCB\nCH -> B\nHH -> N").unwrap_err()
+        );
+    }
+
     #[test]
     fn test_step() {
-        let puzzle = parse_puzzle("day14.example");
+        let puzzle = parse_puzzle("day14.example").unwrap();
         assert_eq!("NCNBCHB", step(puzzle.template, &puzzle.substitutions));
     }
 
     #[test]
     fn example_day14_part1() {
-        let input = parse_puzzle("day14.example");
+        let input = parse_puzzle("day14.example").unwrap();
         assert_eq!(1588, part1(input));
     }
 
     #[test]
     fn exec_day14_part1() {
-        let input = parse_puzzle("day14.txt");
+        let input = parse_puzzle("day14.txt").unwrap();
         println!("Day 14 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example_day14_part2() {
-        let input = parse_puzzle("day14.example");
+        let input = parse_puzzle("day14.example").unwrap();
         assert_eq!(1588, part2(&input, 10));
         assert_eq!(2188189693529, part2(&input, 40));
     }
 
     #[test]
     fn exec_day14_part2() {
-        let input = parse_puzzle("day14.txt");
+        let input = parse_puzzle("day14.txt").unwrap();
         println!("Day 14 Part 1 - {}", part2(&input, 10));
         println!("Day 14 Part 2 - {}", part2(&input, 40));
     }
@@ -1,172 +1,444 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
-    iter::repeat,
-};
+use std::collections::HashSet;
 
-use crate::util::read_lines;
+use crate::util::grid::{Grid, Point, NEIGHBOURS4, NEIGHBOURS8};
+use crate::util::pathfinding::{chebyshev, find_path, manhattan, Algorithm, Graph, Path};
+use crate::util::{parse_file, InputError, ParseError};
+use crate::{Part, SolveError, Solver};
 
-type Puzzle = Vec<Vec<u32>>;
+type Puzzle = Grid<u32>;
 
-pub fn parse_puzzle(file: &str) -> Puzzle {
-    read_lines(file, |line: String| -> Option<Vec<u32>> {
-        Some(line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-    })
-    .unwrap()
-    .collect()
+// Risks wrap round from 9 to 1 when the map is tiled, and every move must
+// cost something, so 0 is not a risk.
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    let grid = Grid::parse(
+        input,
+        |c| c.to_digit(10).filter(|&risk| risk > 0),
+        "a digit from 1 to 9",
+    )?;
+    // With no cells there is nowhere to start or finish.
+    if grid.width() == 0 {
+        return Err(ParseError::new(1, "", "a digit from 1 to 9"));
+    }
+    Ok(grid)
 }
 
-type Node = (usize, usize);
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
+}
 
-pub fn neighbours((x, y): Node) -> impl Iterator<Item = Node> {
-    let mut output = vec![(x + 1, y), (x, y + 1)];
+/// How a path may step from one position to the next.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Moves {
+    /// Up, down, left or right, as in the puzzle.
+    Orthogonal,
+    /// Diagonally as well.
+    Diagonal,
+    /// By any of these `(dx, dy, cost)`, paying `cost` on top of the risk of
+    /// the position moved onto.
+    Custom(Vec<(isize, isize, u32)>),
+}
 
-    if x > 0 {
-        output.push((x - 1, y));
+impl Moves {
+    fn steps(&self) -> Vec<(isize, isize, u32)> {
+        match self {
+            Moves::Orthogonal => NEIGHBOURS4.iter().map(|&(dx, dy)| (dx, dy, 0)).collect(),
+            Moves::Diagonal => NEIGHBOURS8.iter().map(|&(dx, dy)| (dx, dy, 0)).collect(),
+            Moves::Custom(steps) => steps.clone(),
+        }
     }
 
-    if y > 0 {
-        output.push((x, y - 1));
+    // Every move costs at least 1, so never overestimates.
+    fn heuristic(&self, goal: Point) -> impl Fn(Point) -> u32 {
+        let reach = self
+            .steps()
+            .iter()
+            .map(|&(dx, dy, _)| dx.unsigned_abs().max(dy.unsigned_abs()) as u32)
+            .max()
+            .unwrap_or(0);
+        let orthogonal = *self == Moves::Orthogonal;
+        move |node| match reach {
+            0 => 0,
+            _ if orthogonal => manhattan(node, goal),
+            _ => chebyshev(node, goal).div_ceil(reach),
+        }
     }
-
-    output.into_iter()
 }
 
-#[derive(Copy, Clone, Debug)]
-struct MinNode {
-    distance: u32,
-    node: Node,
+/// The risk map repeated `factor` times across and down, each repeat one
+/// riskier than the one before it. Risk levels are worked out as needed, so
+/// large factors cost no more memory than the search itself.
+pub struct TiledMap<'a> {
+    tile: &'a Puzzle,
+    factor: usize,
+    steps: Vec<(isize, isize, u32)>,
 }
 
-impl PartialEq for MinNode {
-    fn eq(&self, other: &Self) -> bool {
-        self.cmp(other) == Ordering::Equal
+impl<'a> TiledMap<'a> {
+    pub fn new(tile: &'a Puzzle, factor: usize, moves: &Moves) -> TiledMap<'a> {
+        TiledMap {
+            tile,
+            factor,
+            steps: moves.steps(),
+        }
     }
-}
 
-impl Eq for MinNode {}
+    pub fn width(&self) -> usize {
+        self.tile.width() * self.factor
+    }
 
-impl PartialOrd for MinNode {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    pub fn height(&self) -> usize {
+        self.tile.height() * self.factor
+    }
+
+    pub fn risk(&self, (x, y): Point) -> Option<u32> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let (width, height) = (self.tile.width(), self.tile.height());
+        Some(special_add(
+            self.tile[(x % width, y % height)],
+            x / width + y / height,
+        ))
+    }
+
+    // The positions one move away, forwards or backwards, and the move's cost.
+    fn moves(&self, (x, y): Point, sign: isize) -> impl Iterator<Item = (Point, u32)> + '_ {
+        self.steps.iter().filter_map(move |&(dx, dy, cost)| {
+            let next = (
+                x.checked_add_signed(dx * sign)?,
+                y.checked_add_signed(dy * sign)?,
+            );
+            self.risk(next).map(|_| (next, cost))
+        })
     }
 }
 
-impl Ord for MinNode {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.distance.cmp(&self.distance)
+// Moving onto a position costs its risk level.
+impl Graph for TiledMap<'_> {
+    type Node = Point;
+
+    fn successors(&self, node: Point) -> Vec<(Point, u32)> {
+        self.moves(node, 1)
+            .map(|(next, cost)| (next, cost + self.risk(next).unwrap()))
+            .collect()
+    }
+
+    fn predecessors(&self, node: Point) -> Vec<(Point, u32)> {
+        let risk = self.risk(node).unwrap();
+        self.moves(node, -1)
+            .map(|(previous, cost)| (previous, cost + risk))
+            .collect()
     }
 }
 
-pub fn part1(input: Puzzle) -> u32 {
-    let height = input.len();
-    let width = input[0].len();
-    let goal = (width - 1, height - 1);
-
-    // Initialise
-    let mut visited: HashSet<Node> = HashSet::new();
-    let mut distances: HashMap<Node, u32> = HashMap::new();
-    distances.insert((0, 0), 0);
-
-    let mut visit_next: BinaryHeap<MinNode> = BinaryHeap::new();
-    visit_next.push(MinNode {
-        distance: 0,
-        node: (0, 0),
-    });
-
-    while let Some(MinNode {
-        distance: current_distance,
-        node: current_node,
-    }) = visit_next.pop()
-    {
-        if visited.contains(&current_node) {
-            continue;
-        }
+/// A route to find across the risk map.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Query {
+    pub start: Point,
+    pub goal: Point,
+    pub moves: Moves,
+    /// How many times the map is tiled across and down.
+    pub factor: usize,
+}
 
-        if current_node == goal {
-            break;
+impl Query {
+    /// The puzzle's route, from the top left to the bottom right of the map
+    /// tiled `factor` times, moving orthogonally.
+    pub fn corner_to_corner(input: &Puzzle, factor: usize) -> Query {
+        Query {
+            start: (0, 0),
+            goal: (input.width() * factor - 1, input.height() * factor - 1),
+            moves: Moves::Orthogonal,
+            factor,
         }
+    }
+
+    /// The least risky path, or `None` if the start or goal is off the map
+    /// or the goal can't be reached.
+    pub fn solve(&self, input: &Puzzle, algorithm: Algorithm) -> Option<Path<Point>> {
+        let map = TiledMap::new(input, self.factor, &self.moves);
+        map.risk(self.start)?;
+        map.risk(self.goal)?;
+        find_path(
+            &map,
+            self.start,
+            self.goal,
+            algorithm,
+            self.moves.heuristic(self.goal),
+        )
+    }
+}
+
+/// The least risky path from the top left to the bottom right of the map
+/// tiled `factor` times.
+pub fn safest_tiled_path(input: &Puzzle, factor: usize, algorithm: Algorithm) -> Path<Point> {
+    Query::corner_to_corner(input, factor)
+        .solve(input, algorithm)
+        .unwrap()
+}
 
-        for (x, y) in neighbours(current_node) {
-            if visited.contains(&(x, y)) {
-                continue;
+/// The least risky path from the top left to the bottom right.
+pub fn safest_path(input: &Puzzle, algorithm: Algorithm) -> Path<Point> {
+    safest_tiled_path(input, 1, algorithm)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Highlight {
+    /// Only the path's risk levels are shown; the rest are `.`.
+    Plain,
+    /// Every risk level is shown, with the path's in bold red.
+    Ansi,
+}
+
+/// Draws the risk map with `path` picked out.
+pub fn render_path(input: &Puzzle, path: &Path<Point>, highlight: Highlight) -> String {
+    let on_path: HashSet<&Point> = path.nodes.iter().collect();
+    input
+        .points()
+        .map(|point| {
+            let risk = input[point].to_string();
+            let cell = match (on_path.contains(&point), highlight) {
+                (true, Highlight::Plain) => risk,
+                (true, Highlight::Ansi) => format!("\x1b[1;31m{}\x1b[0m", risk),
+                (false, Highlight::Plain) => ".".to_string(),
+                (false, Highlight::Ansi) => risk,
+            };
+            if point.0 + 1 == input.width() && point.1 + 1 < input.height() {
+                cell + "\n"
+            } else {
+                cell
             }
+        })
+        .collect()
+}
 
-            if let Some(distance_to_node) = input.get(y).and_then(|line| line.get(x)) {
-                let distance_from_start = current_distance + distance_to_node;
-                let existing_distance = *distances.get(&(x, y)).unwrap_or(&u32::MAX);
-                let new_distance = distance_from_start.min(existing_distance);
-                distances.insert((x, y), new_distance);
-                visit_next.push(MinNode {
-                    distance: new_distance,
-                    node: (x, y),
-                });
+/// Draws the risk map as a binary PPM image, `scale` pixels to a position,
+/// darker for riskier positions and with `path` in red.
+pub fn path_to_ppm(input: &Puzzle, path: &Path<Point>, scale: usize) -> Vec<u8> {
+    let on_path: HashSet<&Point> = path.nodes.iter().collect();
+    let (width, height) = (input.width() * scale, input.height() * scale);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for y in 0..height {
+        for x in 0..width {
+            let point = (x / scale, y / scale);
+            if on_path.contains(&point) {
+                image.extend([255, 0, 0]);
+            } else {
+                let level = (255 - input[point] * 25) as u8;
+                image.extend([level, level, level]);
             }
         }
-
-        visited.insert(current_node);
     }
+    image
+}
+
+pub fn part1_with(input: Puzzle, algorithm: Algorithm) -> u32 {
+    safest_path(&input, algorithm).cost
+}
 
-    distances[&goal]
+pub fn part1(input: Puzzle) -> u32 {
+    part1_with(input, Algorithm::Dijkstra)
 }
 
 pub fn special_add(cell: u32, index: usize) -> u32 {
     (cell - 1 + index as u32) % 9 + 1
 }
 
+pub fn part2_with(input: Puzzle, algorithm: Algorithm) -> u32 {
+    safest_tiled_path(&input, 5, algorithm).cost
+}
+
 pub fn part2(input: Puzzle) -> u32 {
-    let wider_input: Puzzle = input
-        .iter()
-        .map(|line| {
-            repeat(line)
-                .take(5)
-                .enumerate()
-                .map(|(i, segment)| segment.iter().map(move |cell| special_add(*cell, i)))
-                .flatten()
-                .collect()
-        })
-        .collect();
-
-    let bigger_input: Puzzle = repeat(wider_input)
-        .take(5)
-        .enumerate()
-        .map(|(i, segment)| -> Puzzle {
-            segment
-                .iter()
-                .map(move |line| line.iter().map(|cell| special_add(*cell, i)).collect())
-                .collect()
-        })
-        .flatten()
-        .collect();
+    part2_with(input, Algorithm::Dijkstra)
+}
+
+/// Solves either part from raw input, with a choice of search algorithm.
+pub fn solve_with(input: &str, part: Part, algorithm: Algorithm) -> Result<String, SolveError> {
+    let input = from_str(input)?;
+    Ok(match part {
+        Part::One => part1_with(input, algorithm),
+        Part::Two => part2_with(input, algorithm),
+    }
+    .to_string())
+}
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    type Input = Puzzle;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    part1(bigger_input)
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part2(input.clone()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    const ALGORITHMS: [Algorithm; 3] = [
+        Algorithm::Dijkstra,
+        Algorithm::AStar,
+        Algorithm::Bidirectional,
+    ];
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            ParseError::new(2, "0", "a digit from 1 to 9").at_line(2),
+            from_str("19\n10").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_safest_path() {
+        let input = parse_puzzle("day15.example").unwrap();
+        for algorithm in ALGORITHMS {
+            let path = safest_path(&input, algorithm);
+            assert_eq!(40, path.cost);
+            assert_eq!(Some(&(0, 0)), path.nodes.first());
+            assert_eq!(Some(&(9, 9)), path.nodes.last());
+            let risk: u32 = path.nodes[1..].iter().map(|node| input[*node]).sum();
+            assert_eq!(40, risk, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_render_path() {
+        let input = from_str("116\n138\n213").unwrap();
+        let path = safest_path(&input, Algorithm::Dijkstra);
+        assert_eq!(vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)], path.nodes);
+        assert_eq!(
+            "1..\n1..\n213",
+            render_path(&input, &path, Highlight::Plain)
+        );
+        assert_eq!(
+            "\x1b[1;31m1\x1b[0m16",
+            render_path(&input, &path, Highlight::Ansi)
+                .lines()
+                .next()
+                .unwrap()
+        );
+
+        let image = path_to_ppm(&input, &path, 1);
+        let pixels = &image[b"P6\n3 3\n255\n".len()..];
+        assert_eq!([255, 0, 0], pixels[0..3]);
+        assert_eq!([230, 230, 230], pixels[3..6]);
+    }
+
+    #[test]
+    fn test_tiled_map() {
+        let input = parse_puzzle("day15.example").unwrap();
+        let map = TiledMap::new(&input, 5, &Moves::Orthogonal);
+        assert_eq!((50, 50), (map.width(), map.height()));
+        let first_row: String = (0..50)
+            .map(|x| map.risk((x, 0)).unwrap().to_string())
+            .collect();
+        assert_eq!(
+            "11637517422274862853338597396444961841755517295286",
+            first_row
+        );
+        assert_eq!(Some(9), map.risk((49, 49)));
+        assert_eq!(None, map.risk((50, 0)));
+    }
+
+    #[test]
+    fn test_query() {
+        let input = from_str("19\n91").unwrap();
+        let mut query = Query::corner_to_corner(&input, 1);
+        let cost = |query: &Query, algorithm| query.solve(&input, algorithm).map(|p| p.cost);
+        for algorithm in ALGORITHMS {
+            query.moves = Moves::Orthogonal;
+            assert_eq!(Some(10), cost(&query, algorithm));
+            query.moves = Moves::Diagonal;
+            assert_eq!(Some(1), cost(&query, algorithm));
+            query.moves = Moves::Custom(vec![(1, 1, 5)]);
+            assert_eq!(Some(6), cost(&query, algorithm));
+            query.moves = Moves::Custom(vec![(1, 0, 0)]);
+            assert_eq!(None, cost(&query, algorithm));
+        }
+
+        let input = from_str("19\n51").unwrap();
+        let query = Query {
+            start: (1, 1),
+            goal: (0, 0),
+            moves: Moves::Orthogonal,
+            factor: 1,
+        };
+        assert_eq!(
+            vec![(1, 1), (0, 1), (0, 0)],
+            query.solve(&input, Algorithm::AStar).unwrap().nodes
+        );
+        let off_map = Query {
+            goal: (2, 0),
+            ..query
+        };
+        assert_eq!(None, off_map.solve(&input, Algorithm::Dijkstra));
+    }
+
+    #[test]
+    fn test_diagonal_example() {
+        let input = parse_puzzle("day15.example").unwrap();
+        let query = Query {
+            moves: Moves::Diagonal,
+            ..Query::corner_to_corner(&input, 1)
+        };
+        let costs: Vec<u32> = ALGORITHMS
+            .iter()
+            .map(|algorithm| query.solve(&input, *algorithm).unwrap().cost)
+            .collect();
+        assert!(costs[0] < 40);
+        assert!(costs.iter().all(|cost| *cost == costs[0]));
+    }
+
+    #[test]
+    fn test_large_tiling() {
+        let input = parse_puzzle("day15.example").unwrap();
+        let dijkstra = safest_tiled_path(&input, 20, Algorithm::Dijkstra);
+        let bidirectional = safest_tiled_path(&input, 20, Algorithm::Bidirectional);
+        assert_eq!(dijkstra.cost, bidirectional.cost);
+        assert_eq!(Some(&(199, 199)), dijkstra.nodes.last());
+    }
+
+    #[test]
+    fn example_day15_part2_algorithms() {
+        for algorithm in ALGORITHMS {
+            let input = parse_puzzle("day15.example").unwrap();
+            assert_eq!(315, part2_with(input, algorithm), "{:?}", algorithm);
+        }
+    }
+
     #[test]
     fn example_day15_part1() {
-        let input = parse_puzzle("day15.example");
+        let input = parse_puzzle("day15.example").unwrap();
         assert_eq!(40, part1(input));
     }
 
     #[test]
     fn exec_day15_part1() {
-        let input = parse_puzzle("day15.txt");
+        let input = parse_puzzle("day15.txt").unwrap();
         println!("Day 15 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example_day15_part2() {
-        let input = parse_puzzle("day15.example");
+        let input = parse_puzzle("day15.example").unwrap();
         assert_eq!(315, part2(input));
     }
 
     #[test]
     fn exec_day15_part2() {
-        let input = parse_puzzle("day15.txt");
+        let input = parse_puzzle("day15.txt").unwrap();
         println!("Day 15 Part 2 - {}", part2(input));
     }
 }
@@ -1,118 +1,998 @@
-use std::str::Chars;
+use std::fmt;
+use std::io::{self, Read};
+use std::ops::{Range, RangeInclusive};
 
-use itertools::Itertools;
+use num_bigint::BigUint;
 
-pub fn parse_hexstring(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| {
-            let digit = c.to_digit(16).unwrap();
-            format!("{:04b}", digit)
+use crate::util::{parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
+
+/// Packs hexadecimal digits into bytes, two to a byte, padding an odd final
+/// digit with zero bits.
+pub fn parse_hexstring(input: &str) -> Result<Vec<u8>, ParseError> {
+    let digits = input
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or_else(|| ParseError::new(i + 1, &c.to_string(), "a hexadecimal digit"))
         })
-        .join("")
+        .collect::<Result<Vec<u8>, _>>()?;
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect())
+}
+
+/// Decodes a transmission, checking that every operator in it can be
+/// evaluated. Problems are reported at the hex digit they start in.
+pub fn from_str(input: &str) -> Result<Packet, ParseError> {
+    let bytes = parse_hexstring(input.trim())?;
+    let column = |bit: usize| bit / 4 + 1;
+    let packet = decode(&bytes).map_err(|e| match e {
+        DecodeError::Truncated {
+            position, wanted, ..
+        } => ParseError::new(column(position), "", &format!("{} more bits", wanted)),
+        DecodeError::LiteralOverflow { position } => {
+            ParseError::new(column(position), "", "a literal that fits in 64 bits")
+        }
+    })?;
+    packet.validate().map_err(|e| match e {
+        EvalError::UnknownType { type_id, bits } => ParseError::new(
+            column(bits.start),
+            &format!("type {}", type_id),
+            "an operator type",
+        ),
+        EvalError::Arity {
+            type_id,
+            operands,
+            bits,
+        } => ParseError::new(
+            column(bits.start),
+            &format!("{} with {} operands", operator_name(type_id), operands),
+            arity(type_id).map_or("no operands", |(_, expected)| expected),
+        ),
+        EvalError::Overflow { .. } => unreachable!("validating does no arithmetic"),
+    })?;
+    Ok(packet)
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Packet, InputError> {
+    parse_file(file, from_str)
 }
 
-struct BinarySequence<'a> {
-    chars: &'a mut Chars<'a>,
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecodeError {
+    /// Wanted `wanted` more bits at bit `position`, but only `remaining` were left.
+    Truncated {
+        position: usize,
+        wanted: usize,
+        remaining: usize,
+    },
+    /// The literal starting at bit `position` does not fit in a `u64`.
+    LiteralOverflow { position: usize },
 }
 
-impl<'a> BinarySequence<'a> {
-    fn new(input: &'a mut Chars<'a>) -> BinarySequence<'a> {
-        BinarySequence { chars: input }
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated {
+                position,
+                wanted,
+                remaining,
+            } => write!(
+                f,
+                "transmission truncated at bit {}: wanted {} bits, {} left",
+                position, wanted, remaining
+            ),
+            DecodeError::LiteralOverflow { position } => {
+                write!(f, "literal at bit {} does not fit in 64 bits", position)
+            }
+        }
     }
+}
+
+/// Reads big-endian bit fields from a window of a byte slice.
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    end: usize,
+}
 
-    fn eat(&mut self, _: char, n: usize) -> Option<u64> {
-        let substring: String = self.chars.take(n).join("");
-        // print!("{}", repeat(label).take(n).join(""));
-        u64::from_str_radix(&substring, 2).ok()
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            bytes,
+            position: 0,
+            end: bytes.len() * 8,
+        }
+    }
+
+    /// The offset of the next bit, counted from the start of the bytes.
+    pub fn position(&self) -> usize {
+        self.position
     }
 
-    fn take(&mut self, n: usize) -> String {
-        self.chars.take(n).join("")
+    pub fn remaining(&self) -> usize {
+        self.end - self.position
+    }
+
+    fn check(&self, wanted: usize) -> Result<(), DecodeError> {
+        if wanted > self.remaining() {
+            return Err(DecodeError::Truncated {
+                position: self.position,
+                wanted,
+                remaining: self.remaining(),
+            });
+        }
+        Ok(())
     }
+
+    /// Reads the next `n` bits, at most 64, as an unsigned number.
+    pub fn read(&mut self, n: usize) -> Result<u64, DecodeError> {
+        assert!(n <= 64, "cannot read {} bits into a u64", n);
+        self.check(n)?;
+        let mut value = 0;
+        let mut left = n;
+        while left > 0 {
+            let offset = self.position % 8;
+            let take = (8 - offset).min(left);
+            let bits = (self.bytes[self.position / 8] << offset) >> (8 - take);
+            value = value << take | bits as u64;
+            self.position += take;
+            left -= take;
+        }
+        Ok(value)
+    }
+
+    /// Splits off a reader over the next `len` bits and skips past them.
+    pub fn sub_reader(&mut self, len: usize) -> Result<BitReader<'a>, DecodeError> {
+        self.check(len)?;
+        let sub = BitReader {
+            bytes: self.bytes,
+            position: self.position,
+            end: self.position + len,
+        };
+        self.position += len;
+        Ok(sub)
+    }
+}
+
+/// How an operator says where its children end.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LengthType {
+    /// A 15-bit count of the bits its children take up.
+    TotalBits,
+    /// An 11-bit count of its children.
+    PacketCount,
 }
 
-struct PacketResult {
-    version: u64,
-    value: u64,
+impl LengthType {
+    // How many bits the length field takes up.
+    fn width(self) -> usize {
+        match self {
+            LengthType::TotalBits => 15,
+            LengthType::PacketCount => 11,
+        }
+    }
 }
 
-fn packet(sequence: &mut BinarySequence) -> Option<PacketResult> {
-    let mut total_version = sequence.eat('V', 3)?;
-    let packet_type = sequence.eat('T', 3)?;
+/// A decoded packet, with the bit offsets it was read from.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Packet {
+    Literal {
+        version: u8,
+        value: u64,
+        bits: Range<usize>,
+    },
+    Operator {
+        version: u8,
+        type_id: u8,
+        length_type: LengthType,
+        children: Vec<Packet>,
+        bits: Range<usize>,
+    },
+}
+
+impl Packet {
+    /// A literal laid out from bit zero, using as few groups as `value` needs.
+    pub fn literal(version: u8, value: u64) -> Packet {
+        Packet::Literal {
+            version,
+            value,
+            bits: 0..6 + 5 * literal_groups(value),
+        }
+    }
+
+    /// An operator laid out from bit zero, counting its children with the
+    /// shorter header unless there are too many of them.
+    pub fn operator(version: u8, type_id: u8, children: Vec<Packet>) -> Packet {
+        let length_type = if children.len() < 1 << 11 {
+            LengthType::PacketCount
+        } else {
+            LengthType::TotalBits
+        };
+        Packet::operator_with(version, type_id, length_type, children)
+    }
+
+    pub fn operator_with(
+        version: u8,
+        type_id: u8,
+        length_type: LengthType,
+        children: Vec<Packet>,
+    ) -> Packet {
+        let mut end = 7 + length_type.width();
+        let children = children
+            .into_iter()
+            .map(|child| {
+                let by = end - child.bits().start;
+                let child = child.shifted(by);
+                end = child.bits().end;
+                child
+            })
+            .collect();
+        Packet::Operator {
+            version,
+            type_id,
+            length_type,
+            children,
+            bits: 0..end,
+        }
+    }
+
+    // Moves the packet and everything in it `by` bits later.
+    fn shifted(self, by: usize) -> Packet {
+        match self {
+            Packet::Literal {
+                version,
+                value,
+                bits,
+            } => Packet::Literal {
+                version,
+                value,
+                bits: bits.start + by..bits.end + by,
+            },
+            Packet::Operator {
+                version,
+                type_id,
+                length_type,
+                children,
+                bits,
+            } => Packet::Operator {
+                version,
+                type_id,
+                length_type,
+                children: children.into_iter().map(|c| c.shifted(by)).collect(),
+                bits: bits.start + by..bits.end + by,
+            },
+        }
+    }
+
+    pub fn version(&self) -> u8 {
+        match self {
+            Packet::Literal { version, .. } | Packet::Operator { version, .. } => *version,
+        }
+    }
 
-    if packet_type == 4 {
-        // Literal
-        let mut literal = 0;
+    pub fn bits(&self) -> Range<usize> {
+        match self {
+            Packet::Literal { bits, .. } | Packet::Operator { bits, .. } => bits.clone(),
+        }
+    }
+
+    pub fn children(&self) -> &[Packet] {
+        match self {
+            Packet::Literal { .. } => &[],
+            Packet::Operator { children, .. } => children,
+        }
+    }
+
+    /// Combines the packets bottom up, handing `f` each packet along with
+    /// what its children combined to.
+    pub fn fold<T>(&self, f: &impl Fn(&Packet, Vec<T>) -> T) -> T {
+        let children = self.children().iter().map(|child| child.fold(f)).collect();
+        f(self, children)
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        self.fold(&|packet, children: Vec<u64>| {
+            packet.version() as u64 + children.iter().sum::<u64>()
+        })
+    }
+
+    /// Checks that every operator has a known type and the right number of
+    /// operands, so that evaluating can only fail by overflowing.
+    pub fn validate(&self) -> Result<(), EvalError> {
+        self.fold(&|packet, children: Vec<Result<(), EvalError>>| {
+            children.into_iter().collect::<Result<(), _>>()?;
+            match packet {
+                Packet::Literal { .. } => Ok(()),
+                Packet::Operator {
+                    type_id,
+                    children,
+                    bits,
+                    ..
+                } => check_operands(*type_id, children.len(), bits),
+            }
+        })
+    }
+
+    /// Evaluates in `u64`, failing if any sum or product overflows.
+    pub fn evaluate(&self) -> Result<u64, EvalError> {
+        evaluate_as(self)
+    }
+
+    /// Evaluates without any limit on the size of the result.
+    pub fn evaluate_big(&self) -> Result<BigUint, EvalError> {
+        evaluate_as(self)
+    }
+}
+
+/// What an operator can be applied to.
+trait Value: Ord + Clone + From<u64> {
+    fn add(&self, other: &Self) -> Option<Self>;
+    fn mul(&self, other: &Self) -> Option<Self>;
+}
+
+impl Value for u64 {
+    fn add(&self, other: &u64) -> Option<u64> {
+        self.checked_add(*other)
+    }
+
+    fn mul(&self, other: &u64) -> Option<u64> {
+        self.checked_mul(*other)
+    }
+}
+
+impl Value for BigUint {
+    fn add(&self, other: &BigUint) -> Option<BigUint> {
+        Some(self + other)
+    }
+
+    fn mul(&self, other: &BigUint) -> Option<BigUint> {
+        Some(self * other)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum EvalError {
+    /// An operator packet whose type id is not an operator.
+    UnknownType { type_id: u8, bits: Range<usize> },
+    /// An operator with the wrong number of operands.
+    Arity {
+        type_id: u8,
+        operands: usize,
+        bits: Range<usize>,
+    },
+    /// A sum or product too large for a `u64`.
+    Overflow { type_id: u8, bits: Range<usize> },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::UnknownType { type_id, bits } => {
+                write!(f, "unknown operator type {} at bits {:?}", type_id, bits)
+            }
+            EvalError::Arity {
+                type_id,
+                operands,
+                bits,
+            } => write!(
+                f,
+                "operator type {} at bits {:?} expected {}, found {}",
+                type_id,
+                bits,
+                arity(*type_id).map_or("no operands", |(_, expected)| expected),
+                operands
+            ),
+            EvalError::Overflow { type_id, bits } => {
+                write!(f, "operator type {} at bits {:?} overflowed", type_id, bits)
+            }
+        }
+    }
+}
+
+/// How many operands an operator takes, and how to describe that, or
+/// `None` if the type id is not an operator.
+fn arity(type_id: u8) -> Option<(RangeInclusive<usize>, &'static str)> {
+    match type_id {
+        0..=3 => Some((1..=usize::MAX, "at least one operand")),
+        5..=7 => Some((2..=2, "two operands")),
+        _ => None,
+    }
+}
+
+fn check_operands(type_id: u8, operands: usize, bits: &Range<usize>) -> Result<(), EvalError> {
+    let (expected, _) = arity(type_id).ok_or(EvalError::UnknownType {
+        type_id,
+        bits: bits.clone(),
+    })?;
+    if !expected.contains(&operands) {
+        return Err(EvalError::Arity {
+            type_id,
+            operands,
+            bits: bits.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn evaluate_as<V: Value>(packet: &Packet) -> Result<V, EvalError> {
+    packet.fold(&|packet, values: Vec<Result<V, EvalError>>| {
+        let (type_id, bits) = match packet {
+            Packet::Literal { value, .. } => return Ok(V::from(*value)),
+            Packet::Operator { type_id, bits, .. } => (*type_id, bits.clone()),
+        };
+        let values = values.into_iter().collect::<Result<Vec<V>, _>>()?;
+        check_operands(type_id, values.len(), &bits)?;
+        let overflow = || EvalError::Overflow {
+            type_id,
+            bits: bits.clone(),
+        };
+        let first = values[0].clone();
+        let mut rest = values[1..].iter();
+        Ok(match type_id {
+            0 => rest
+                .try_fold(first, |acc, v| acc.add(v))
+                .ok_or_else(overflow)?,
+            1 => rest
+                .try_fold(first, |acc, v| acc.mul(v))
+                .ok_or_else(overflow)?,
+            2 => values.into_iter().min().unwrap(),
+            3 => values.into_iter().max().unwrap(),
+            5 => V::from((values[0] > values[1]) as u64),
+            6 => V::from((values[0] < values[1]) as u64),
+            _ => V::from((values[0] == values[1]) as u64),
+        })
+    })
+}
+
+pub fn parse_packet(reader: &mut BitReader) -> Result<Packet, DecodeError> {
+    let start = reader.position();
+    let version = reader.read(3)? as u8;
+    let type_id = reader.read(3)? as u8;
+
+    if type_id == 4 {
+        let mut value = 0;
         loop {
-            let keep_reading = sequence.eat('R', 1)?;
-            literal <<= 4;
-            literal += sequence.eat('L', 4)?;
+            let keep_reading = reader.read(1)?;
+            if value >> 60 != 0 {
+                return Err(DecodeError::LiteralOverflow { position: start });
+            }
+            value <<= 4;
+            value += reader.read(4)?;
 
             if keep_reading == 0 {
                 break;
             }
         }
-        Some(PacketResult {
-            version: total_version,
-            value: literal,
-        })
+        return Ok(Packet::Literal {
+            version,
+            value,
+            bits: start..reader.position(),
+        });
+    }
+
+    let mut children = vec![];
+    let length_type = if reader.read(1)? == 0 {
+        let bit_length = reader.read(LengthType::TotalBits.width())?;
+        let mut subreader = reader.sub_reader(bit_length as usize)?;
+        while subreader.remaining() > 0 {
+            children.push(parse_packet(&mut subreader)?);
+        }
+        LengthType::TotalBits
     } else {
-        // Operator
-        let length_type = sequence.eat('I', 1)?;
-        let mut subpackets: Vec<u64> = vec![];
-
-        if length_type == 0 {
-            let bit_length = sequence.eat('l', 15)?;
-            let substring = sequence.take(bit_length as usize);
-            let mut chars = substring.chars();
-            let mut subsequence = BinarySequence::new(&mut chars);
-            while let Some(subpacket) = packet(&mut subsequence) {
-                total_version += subpacket.version;
-                subpackets.push(subpacket.value);
+        let packet_count = reader.read(LengthType::PacketCount.width())?;
+        for _ in 0..packet_count {
+            children.push(parse_packet(reader)?);
+        }
+        LengthType::PacketCount
+    };
+    Ok(Packet::Operator {
+        version,
+        type_id,
+        length_type,
+        children,
+        bits: start..reader.position(),
+    })
+}
+
+/// Decodes the outermost packet of a transmission, ignoring any padding.
+pub fn decode(input: &[u8]) -> Result<Packet, DecodeError> {
+    parse_packet(&mut BitReader::new(input))
+}
+
+fn literal_groups(value: u64) -> usize {
+    let significant = 64 - value.leading_zeros() as usize;
+    significant.div_ceil(4).max(1)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum EncodeError {
+    /// `value` does not fit in the `bits`-bit `field`.
+    FieldTooLarge {
+        field: &'static str,
+        value: u64,
+        bits: usize,
+    },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::FieldTooLarge { field, value, bits } => {
+                write!(f, "{} {} does not fit in {} bits", field, value, bits)
             }
-        } else {
-            let packet_count = sequence.eat('c', 11)?;
-            for _ in 0..packet_count {
-                let subpacket = packet(sequence).unwrap();
-                total_version += subpacket.version;
-                subpackets.push(subpacket.value);
-            }
-        }
-
-        let result: u64 = match packet_type {
-            0 => subpackets.iter().sum(),
-            1 => subpackets.iter().product(),
-            2 => *subpackets.iter().min().unwrap(),
-            3 => *subpackets.iter().max().unwrap(),
-            5 => (subpackets[0] > subpackets[1]) as u64,
-            6 => (subpackets[0] < subpackets[1]) as u64,
-            7 => (subpackets[0] == subpackets[1]) as u64,
-            _ => unimplemented!(),
+        }
+    }
+}
+
+/// Appends big-endian bit fields to a growing byte buffer.
+#[derive(Default, Debug)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        BitWriter::default()
+    }
+
+    /// How many bits have been written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes the low `n` bits of `value`, refusing values that need more.
+    pub fn write(&mut self, field: &'static str, value: u64, n: usize) -> Result<(), EncodeError> {
+        if n < 64 && value >> n != 0 {
+            return Err(EncodeError::FieldTooLarge {
+                field,
+                value,
+                bits: n,
+            });
+        }
+        for i in (0..n).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = (value >> i) as u8 & 1;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// The bytes written so far, with the last one padded with zero bits.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+fn encoded_len(packet: &Packet) -> usize {
+    match packet {
+        Packet::Literal { value, .. } => 6 + 5 * literal_groups(*value),
+        Packet::Operator {
+            length_type,
+            children,
+            ..
+        } => 7 + length_type.width() + children.iter().map(encoded_len).sum::<usize>(),
+    }
+}
+
+/// Writes `packet` using its length types, ignoring its bit offsets.
+pub fn write_packet(writer: &mut BitWriter, packet: &Packet) -> Result<(), EncodeError> {
+    writer.write("version", packet.version() as u64, 3)?;
+    match packet {
+        Packet::Literal { value, .. } => {
+            writer.write("type id", 4, 3)?;
+            let groups = literal_groups(*value);
+            for group in (0..groups).rev() {
+                writer.write("group marker", (group > 0) as u64, 1)?;
+                writer.write("group", value >> (4 * group) & 0xF, 4)?;
+            }
+        }
+        Packet::Operator {
+            type_id,
+            length_type,
+            children,
+            ..
+        } => {
+            writer.write("type id", *type_id as u64, 3)?;
+            match length_type {
+                LengthType::TotalBits => {
+                    writer.write("length type", 0, 1)?;
+                    let bits = children.iter().map(encoded_len).sum::<usize>();
+                    writer.write("bit length", bits as u64, length_type.width())?;
+                }
+                LengthType::PacketCount => {
+                    writer.write("length type", 1, 1)?;
+                    writer.write("packet count", children.len() as u64, length_type.width())?;
+                }
+            }
+            for child in children {
+                write_packet(writer, child)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn encode(packet: &Packet) -> Result<Vec<u8>, EncodeError> {
+    let mut writer = BitWriter::new();
+    write_packet(&mut writer, packet)?;
+    Ok(writer.into_bytes())
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+/// Operator names in expressions, with their type ids.
+const OPERATORS: [(&str, u8); 7] = [
+    ("sum", 0),
+    ("product", 1),
+    ("min", 2),
+    ("max", 3),
+    ("gt", 5),
+    ("lt", 6),
+    ("eq", 7),
+];
+
+struct ExpressionParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> ExpressionParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        let found = self.peek().map_or(String::new(), |c| c.to_string());
+        ParseError::new(self.position + 1, &found, expected)
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        while self.peek().is_some_and(&f) {
+            self.position += 1;
+        }
+        &self.input[start..self.position]
+    }
+
+    fn expression(&mut self) -> Result<Packet, ParseError> {
+        self.skip_spaces();
+        let start = self.position;
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() => {
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                let value = digits
+                    .parse()
+                    .map_err(|_| ParseError::new(start + 1, digits, "a 64-bit number"))?;
+                Ok(Packet::literal(0, value))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name = self.take_while(|c| c.is_ascii_alphabetic());
+                let type_id = OPERATORS
+                    .iter()
+                    .find(|(operator, _)| *operator == name)
+                    .map(|(_, type_id)| *type_id)
+                    .ok_or_else(|| ParseError::new(start + 1, name, "an operator name"))?;
+                let children = self.arguments()?;
+                let (operands, expected) = arity(type_id).unwrap();
+                if !operands.contains(&children.len()) {
+                    return Err(ParseError::new(start + 1, name, expected));
+                }
+                Ok(Packet::operator(0, type_id, children))
+            }
+            _ => Err(self.error("a number or an operator")),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Packet>, ParseError> {
+        self.skip_spaces();
+        if self.peek() != Some('(') {
+            return Err(self.error("`(`"));
+        }
+        self.position += 1;
+        let mut children = vec![];
+        self.skip_spaces();
+        if self.peek() == Some(')') {
+            self.position += 1;
+            return Ok(children);
+        }
+        loop {
+            children.push(self.expression()?);
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(')') => {
+                    self.position += 1;
+                    return Ok(children);
+                }
+                _ => return Err(self.error("`,` or `)`")),
+            }
+        }
+    }
+}
+
+/// Parses an expression such as `sum(1, max(3, 4))` into packets, all with
+/// version 0. The operators are `sum`, `product`, `min`, `max`, `gt`, `lt`
+/// and `eq`.
+pub fn parse_expression(input: &str) -> Result<Packet, ParseError> {
+    let mut parser = ExpressionParser { input, position: 0 };
+    let packet = parser.expression()?;
+    parser.skip_spaces();
+    if parser.peek().is_some() {
+        return Err(parser.error("the end of the expression"));
+    }
+    Ok(packet)
+}
+
+fn operator_name(type_id: u8) -> String {
+    OPERATORS
+        .iter()
+        .find(|(_, id)| *id == type_id)
+        .map_or_else(|| format!("op{}", type_id), |(name, _)| name.to_string())
+}
+
+/// Lists a packet and everything in it, one per line and indented by depth,
+/// with where each was read from and what it evaluates to.
+pub fn disassemble(packet: &Packet) -> String {
+    let mut lines = vec![];
+    disassemble_into(packet, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn disassemble_into(packet: &Packet, depth: usize, lines: &mut Vec<String>) {
+    let value = match packet.evaluate_big() {
+        Ok(value) => value.to_string(),
+        Err(e) => format!("error ({})", e),
+    };
+    let bits = packet.bits();
+    let description = match packet {
+        Packet::Literal { version, .. } => {
+            format!("literal v{} bits {}..{}", version, bits.start, bits.end)
+        }
+        Packet::Operator {
+            version,
+            type_id,
+            length_type,
+            children,
+            ..
+        } => {
+            let length = match length_type {
+                LengthType::TotalBits => format!(
+                    "total bits {}",
+                    children.iter().map(|c| c.bits().len()).sum::<usize>()
+                ),
+                LengthType::PacketCount => format!("packet count {}", children.len()),
+            };
+            format!(
+                "{} v{} bits {}..{} {}",
+                operator_name(*type_id),
+                version,
+                bits.start,
+                bits.end,
+                length
+            )
+        }
+    };
+    lines.push(format!("{}{} = {}", "  ".repeat(depth), description, value));
+    for child in packet.children() {
+        disassemble_into(child, depth + 1, lines);
+    }
+}
+
+// How tightly an expression binds, so that looser children get brackets.
+fn precedence(packet: &Packet) -> u8 {
+    match packet {
+        Packet::Operator {
+            type_id, children, ..
+        } if children.len() > 1 => match type_id {
+            0 => 2,
+            1 => 3,
+            5..=7 => 1,
+            _ => 4,
+        },
+        _ => 4,
+    }
+}
+
+/// Writes the packet as an expression, such as `(5 + 3) * max(2, 9)`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (type_id, children) = match self {
+            Packet::Literal { value, .. } => return write!(f, "{}", value),
+            Packet::Operator {
+                type_id, children, ..
+            } => (*type_id, children),
         };
-        Some(PacketResult {
-            version: total_version,
-            value: result,
-        })
+        let symbol = match type_id {
+            0 => " + ",
+            1 => " * ",
+            5 => " > ",
+            6 => " < ",
+            7 => " == ",
+            _ => "",
+        };
+        let tightness = precedence(self);
+        if symbol.is_empty() || tightness == 4 {
+            write!(f, "{}(", operator_name(type_id))?;
+            for (i, child) in children.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", child)?;
+            }
+            return write!(f, ")");
+        }
+        for (i, child) in children.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", symbol)?;
+            }
+            // Sums and products are associative, but comparisons are not.
+            if precedence(child) < tightness || (tightness == 1 && precedence(child) == 1) {
+                write!(f, "({})", child)?;
+            } else {
+                write!(f, "{}", child)?;
+            }
+        }
+        Ok(())
     }
 }
 
-pub fn part1(input: String) -> u64 {
-    // println!("{}", input);
-    let mut chars = input.chars();
-    let mut sequence = BinarySequence::new(&mut chars);
-    let result = packet(&mut sequence);
-    // println!();
-    result.unwrap().version
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// The packet starting at byte `offset` could not be decoded.
+    Decode {
+        offset: usize,
+        error: DecodeError,
+    },
 }
 
-pub fn part2(input: String) -> u64 {
-    // println!("{}", input);
-    let mut chars = input.chars();
-    let mut sequence = BinarySequence::new(&mut chars);
-    let result = packet(&mut sequence);
-    // println!();
-    result.unwrap().value
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{}", e),
+            StreamError::Decode { offset, error } => write!(f, "at byte {}: {}", offset, error),
+        }
+    }
+}
+
+// How much to read at once, unless a packet needs more.
+const CHUNK: usize = 8192;
+
+/// Decodes successive top-level packets from a stream of bytes, yielding
+/// each with the byte offset it starts at. Every packet starts on a fresh
+/// byte. A zero byte is taken as padding wherever no well-formed packet
+/// starts at it, so packets that begin with one are still read.
+pub struct Packets<R> {
+    source: R,
+    buffer: Vec<u8>,
+    offset: usize,
+    eof: bool,
+    failed: bool,
+}
+
+impl<R: Read> Packets<R> {
+    pub fn new(source: R) -> Packets<R> {
+        Packets {
+            source,
+            buffer: vec![],
+            offset: 0,
+            eof: false,
+            failed: false,
+        }
+    }
+
+    // Reads at least as much again as is already buffered.
+    fn fill(&mut self) -> io::Result<()> {
+        let wanted = CHUNK.max(self.buffer.len()) as u64;
+        let read = (&mut self.source)
+            .take(wanted)
+            .read_to_end(&mut self.buffer)?;
+        self.eof = read == 0;
+        Ok(())
+    }
+
+    fn next_packet(&mut self) -> Result<Option<(usize, Packet)>, StreamError> {
+        loop {
+            if self.buffer.is_empty() {
+                if self.eof {
+                    return Ok(None);
+                }
+                self.fill().map_err(StreamError::Io)?;
+                continue;
+            }
+            let decoded = decode(&self.buffer);
+            // Runs of zero bits decode as empty sums, which are never valid.
+            let well_formed = decoded.as_ref().is_ok_and(|p| p.validate().is_ok());
+            match decoded {
+                Err(DecodeError::Truncated { .. }) if !self.eof => {
+                    self.fill().map_err(StreamError::Io)?
+                }
+                _ if self.buffer[0] == 0 && !well_formed => {
+                    self.buffer.drain(..1);
+                    self.offset += 1;
+                }
+                Ok(packet) => {
+                    let used = packet.bits().end.div_ceil(8);
+                    self.buffer.drain(..used);
+                    let offset = self.offset;
+                    self.offset += used;
+                    return Ok(Some((offset, packet)));
+                }
+                Err(error) => {
+                    return Err(StreamError::Decode {
+                        offset: self.offset,
+                        error,
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Packets<R> {
+    type Item = Result<(usize, Packet), StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_packet();
+        self.failed = next.is_err();
+        next.transpose()
+    }
+}
+
+pub fn part1(input: Packet) -> u64 {
+    input.version_sum()
+}
+
+/// Evaluates without a limit on size, so that only packets [`from_str`]
+/// would have rejected can fail.
+pub fn part2(input: Packet) -> BigUint {
+    input
+        .evaluate_big()
+        .expect("operators are checked when parsing")
+}
+
+pub struct Day16;
+
+impl Solver for Day16 {
+    type Input = Packet;
+    type Answer1 = u64;
+    type Answer2 = BigUint;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<BigUint, SolveError> {
+        Ok(part2(input.clone()))
+    }
 }
 
 #[cfg(test)]
@@ -121,44 +1001,428 @@ mod test {
 
     #[test]
     fn test_parse_hexstring() {
-        assert_eq!("0001", parse_hexstring("1"));
+        assert_eq!(vec![0x10], parse_hexstring("1").unwrap());
+        assert_eq!(
+            vec![0x38, 0x00, 0x6F, 0x45, 0x29, 0x12, 0x00],
+            parse_hexstring("38006F45291200").unwrap()
+        );
+        assert_eq!(vec![0xD2, 0xFE, 0x28], parse_hexstring("d2fe28").unwrap());
+        assert_eq!(
+            ParseError::new(3, "G", "a hexadecimal digit"),
+            parse_hexstring("D2G").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_bit_reader() {
+        // 110100101111111000101000
+        let bytes = parse_hexstring("D2FE28").unwrap();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(Ok(6), reader.read(3));
+        assert_eq!(Ok(4), reader.read(3));
+        assert_eq!(Ok(0b10111), reader.read(5));
+        assert_eq!(11, reader.position());
+
+        let mut sub = reader.sub_reader(10).unwrap();
+        assert_eq!(21, reader.position());
+        assert_eq!(Ok(0b1111000101), sub.read(10));
+        assert_eq!(0, sub.remaining());
+        assert_eq!(
+            Err(DecodeError::Truncated {
+                position: 21,
+                wanted: 1,
+                remaining: 0
+            }),
+            sub.read(1)
+        );
+        assert_eq!(Ok(0), reader.read(3));
+        assert!(reader.sub_reader(1).is_err());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let bytes = parse_hexstring("38006F4529").unwrap();
         assert_eq!(
-            "00111000000000000110111101000101001010010001001000000000",
-            parse_hexstring("38006F45291200")
+            Err(DecodeError::Truncated {
+                position: 22,
+                wanted: 27,
+                remaining: 18
+            }),
+            decode(&bytes)
         );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            Ok(Packet::Literal {
+                version: 6,
+                value: 2021,
+                bits: 0..21
+            }),
+            decode(&parse_hexstring("D2FE28").unwrap())
+        );
+        let packet = decode(&parse_hexstring("38006F45291200").unwrap()).unwrap();
+        assert_eq!(
+            Packet::Operator {
+                version: 1,
+                type_id: 6,
+                length_type: LengthType::TotalBits,
+                children: vec![
+                    Packet::Literal {
+                        version: 6,
+                        value: 10,
+                        bits: 22..33
+                    },
+                    Packet::Literal {
+                        version: 2,
+                        value: 20,
+                        bits: 33..49
+                    },
+                ],
+                bits: 0..49
+            },
+            packet
+        );
+        assert_eq!(Ok(1), packet.evaluate());
+        let packet = decode(&parse_hexstring("EE00D40C823060").unwrap()).unwrap();
+        assert!(matches!(
+            packet,
+            Packet::Operator {
+                length_type: LengthType::PacketCount,
+                ..
+            }
+        ));
+        let leaves = packet.fold(&|_, children: Vec<usize>| children.iter().sum::<usize>().max(1));
+        assert_eq!(3, leaves);
+    }
+
+    #[test]
+    fn test_encode() {
+        let packet = parse_expression("sum(1, max(3,4))").unwrap();
+        assert_eq!(Ok(5), packet.evaluate());
+        let bytes = encode(&packet).unwrap();
+        assert_eq!(Ok(packet), decode(&bytes));
+
+        let packet = decode(&parse_hexstring("38006F45291200").unwrap()).unwrap();
+        assert_eq!("38006F45291200", to_hex(&encode(&packet).unwrap()));
         assert_eq!(
-            "11101110000000001101010000001100100000100011000001100000",
-            parse_hexstring("EE00D40C823060")
+            Err(EncodeError::FieldTooLarge {
+                field: "version",
+                value: 8,
+                bits: 3
+            }),
+            encode(&Packet::literal(8, 1))
+        );
+    }
+
+    #[test]
+    fn test_parse_expression_error() {
+        assert_eq!(
+            ParseError::new(5, "", "a number or an operator"),
+            parse_expression("max(").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "avg", "an operator name"),
+            parse_expression("avg(1)").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "lt", "two operands"),
+            parse_expression("lt(1, 2, 3)").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(7, "2", "`,` or `)`"),
+            parse_expression("sum(1 2)").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(7, ")", "the end of the expression"),
+            parse_expression("min(1))").unwrap_err()
+        );
+        // Columns count bytes, so an ideographic space takes up three.
+        assert_eq!(
+            ParseError::new(10, "é", "a number or an operator"),
+            parse_expression("sum(1,\u{3000}é)").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_expression_unicode_spaces() {
+        assert_eq!(
+            parse_expression("sum(1, 2)").unwrap(),
+            parse_expression("sum(1,\u{3000}2)\u{a0}").unwrap()
+        );
+    }
+
+    // A small xorshift generator, so the property test needs no extra crates.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn random_packet(random: &mut Random, depth: usize) -> Packet {
+        let version = random.below(8) as u8;
+        if depth == 0 || random.below(3) == 0 {
+            let value = random.next() >> random.below(64);
+            return Packet::literal(version, value);
+        }
+        let type_id = [0, 1, 2, 3, 5, 6, 7][random.below(7) as usize];
+        let children = (0..random.below(4))
+            .map(|_| random_packet(random, depth - 1))
+            .collect();
+        let length_type = if random.below(2) == 0 {
+            LengthType::TotalBits
+        } else {
+            LengthType::PacketCount
+        };
+        Packet::operator_with(version, type_id, length_type, children)
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let mut random = Random(0x2021_1216);
+        for _ in 0..500 {
+            let packet = random_packet(&mut random, 4);
+            let bytes = encode(&packet).unwrap();
+            assert_eq!(packet.bits().end.div_ceil(8), bytes.len());
+            assert_eq!(Ok(packet), decode(&bytes));
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let packet = parse_expression("product(4294967296, sum(4294967295, 1))").unwrap();
+        assert_eq!(
+            Err(EvalError::Overflow {
+                type_id: 1,
+                bits: 0..packet.bits().end
+            }),
+            packet.evaluate()
+        );
+        assert_eq!(Ok(BigUint::from(1u128 << 64)), packet.evaluate_big());
+
+        let packet = Packet::operator(0, 5, vec![Packet::literal(0, 1)]);
+        assert_eq!(
+            Err(EvalError::Arity {
+                type_id: 5,
+                operands: 1,
+                bits: 0..29
+            }),
+            packet.evaluate()
+        );
+        let packet = Packet::operator(0, 4, vec![Packet::literal(0, 1)]);
+        assert_eq!(
+            Err(EvalError::UnknownType {
+                type_id: 4,
+                bits: 0..29
+            }),
+            packet.evaluate()
+        );
+    }
+
+    #[test]
+    fn test_literal_overflow() {
+        // A literal of seventeen groups, one more than a u64 holds.
+        let mut writer = BitWriter::new();
+        writer.write("header", 0b000_100, 6).unwrap();
+        for group in (0..17).rev() {
+            let marker = if group > 0 { 0x10 } else { 0 };
+            writer.write("group", marker | 0xF, 5).unwrap();
+        }
+        assert_eq!(
+            Err(DecodeError::LiteralOverflow { position: 0 }),
+            decode(&writer.into_bytes())
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let packet = decode(&parse_hexstring("9C0141080250320F1802104A08").unwrap()).unwrap();
+        assert_eq!(
+            "eq v4 bits 0..102 total bits 80 = 1
+  sum v2 bits 22..62 packet count 2 = 4
+    literal v2 bits 40..51 = 1
+    literal v4 bits 51..62 = 3
+  product v6 bits 62..102 packet count 2 = 4
+    literal v0 bits 80..91 = 2
+    literal v2 bits 91..102 = 2",
+            disassemble(&packet)
+        );
+        assert_eq!("1 + 3 == 2 * 2", packet.to_string());
+    }
+
+    #[test]
+    fn test_display() {
+        for (expression, infix) in [
+            ("product(sum(5, 3), max(2, 9))", "(5 + 3) * max(2, 9)"),
+            ("sum(1, product(2, 3))", "1 + 2 * 3"),
+            ("eq(lt(1, 2), gt(3, 4))", "(1 < 2) == (3 > 4)"),
+            ("sum(sum(7), product(8, 9), 1)", "sum(7) + 8 * 9 + 1"),
+        ] {
+            assert_eq!(infix, parse_expression(expression).unwrap().to_string());
+        }
+    }
+
+    // Hands out one byte per read, to make the decoder ask for more.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((byte, rest)), Some(slot)) => {
+                    *slot = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_packets() {
+        let mut bytes = parse_hexstring("D2FE28").unwrap();
+        bytes.extend(parse_hexstring("38006F45291200").unwrap());
+        bytes.extend(encode(&parse_expression("max(3, 4)").unwrap()).unwrap());
+        bytes.extend([0, 0, 0, 0]);
+        let packets = Packets::new(Trickle(&bytes))
+            .map(|p| p.map(|(offset, packet)| (offset, packet.evaluate().unwrap())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![(0, 2021), (3, 1), (10, 4)], packets);
+        assert_eq!(0, Packets::new(&[0u8, 0][..]).count());
+    }
+
+    #[test]
+    fn test_packets_larger_than_chunk() {
+        let literals = (0..2000).map(|i| Packet::literal(1, 1 << 60 | i)).collect();
+        let packet = Packet::operator(0, 0, literals);
+        let mut bytes = encode(&packet).unwrap();
+        assert!(bytes.len() > CHUNK);
+        let size = bytes.len();
+        bytes.extend(parse_hexstring("D2FE28").unwrap());
+        let packets = Packets::new(&bytes[..])
+            .map(|p| p.map(|(offset, packet)| (offset, packet.version_sum())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![(0, 2000), (size, 6)], packets);
+    }
+
+    #[test]
+    fn test_packets_padded_between() {
+        let bytes = parse_hexstring("D2FE2800D2FE280000000038006F45291200").unwrap();
+        let offsets = Packets::new(Trickle(&bytes))
+            .map(|p| p.map(|(offset, _)| offset))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![0, 4, 11], offsets);
+    }
+
+    #[test]
+    fn test_packets_starting_with_zero() {
+        let sum = Packet::operator_with(0, 0, LengthType::TotalBits, vec![Packet::literal(0, 5)]);
+        let mut bytes = encode(&sum).unwrap();
+        assert_eq!(0, bytes[0]);
+        let size = bytes.len();
+        bytes.extend(encode(&sum).unwrap());
+        bytes.extend([0, 0]);
+        bytes.extend(parse_hexstring("D2FE28").unwrap());
+        let packets = Packets::new(Trickle(&bytes))
+            .map(|p| p.map(|(offset, packet)| (offset, packet.evaluate().unwrap())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![(0, 5), (size, 5), (2 * size + 2, 2021)], packets);
+
+        let many = parse_expression(&format!("sum({})", vec!["1"; 2048].join(","))).unwrap();
+        let mut bytes = encode(&many).unwrap();
+        bytes.extend(parse_hexstring("D2FE28").unwrap());
+        let packets = Packets::new(&bytes[..])
+            .map(|p| p.map(|(_, packet)| packet.evaluate().unwrap()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![2048, 2021], packets);
+    }
+
+    #[test]
+    fn test_packets_truncated() {
+        let mut bytes = parse_hexstring("D2FE28").unwrap();
+        bytes.extend(parse_hexstring("38006F4529").unwrap());
+        let mut packets = Packets::new(&bytes[..]);
+        assert!(packets.next().unwrap().is_ok());
+        match packets.next() {
+            Some(Err(StreamError::Decode { offset, error })) => {
+                assert_eq!(3, offset);
+                assert!(matches!(error, DecodeError::Truncated { .. }));
+            }
+            other => panic!("expected a decode error, got {:?}", other),
+        }
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn test_from_str_error() {
+        assert_eq!(
+            ParseError::new(6, "", "27 more bits"),
+            from_str("38006F4529").unwrap_err()
+        );
+        let lone = encode(&Packet::operator(0, 6, vec![Packet::literal(0, 1)])).unwrap();
+        assert_eq!(
+            ParseError::new(1, "lt with 1 operands", "two operands"),
+            from_str(&to_hex(&lone)).unwrap_err()
+        );
+        let overflowing = "product(4294967296, 4294967296)";
+        let bytes = encode(&parse_expression(overflowing).unwrap()).unwrap();
+        assert_eq!(
+            BigUint::from(1u128 << 64),
+            part2(from_str(&to_hex(&bytes)).unwrap())
         );
     }
 
     #[test]
     fn example_day16_part1() {
-        assert_eq!(6, part1(parse_hexstring("D2FE28")));
-        assert_eq!(7 + 2 + 4 + 1, part1(parse_hexstring("EE00D40C823060")));
-        assert_eq!(16, part1(parse_hexstring("8A004A801A8002F478")));
-        assert_eq!(12, part1(parse_hexstring("620080001611562C8802118E34")));
-        assert_eq!(23, part1(parse_hexstring("C0015000016115A2E0802F182340")));
-        assert_eq!(31, part1(parse_hexstring("A0016C880162017C3686B18A3D4780")));
+        assert_eq!(6, part1(from_str("D2FE28").unwrap()));
+        assert_eq!(7 + 2 + 4 + 1, part1(from_str("EE00D40C823060").unwrap()));
+        assert_eq!(16, part1(from_str("8A004A801A8002F478").unwrap()));
+        assert_eq!(12, part1(from_str("620080001611562C8802118E34").unwrap()));
+        assert_eq!(23, part1(from_str("C0015000016115A2E0802F182340").unwrap()));
+        assert_eq!(
+            31,
+            part1(from_str("A0016C880162017C3686B18A3D4780").unwrap())
+        );
     }
 
     #[test]
     fn exec_day16_part1() {
-        let input = "420D4900B8F31EFE7BD9DA455401AB80021504A2745E1007A21C1C862801F54AD0765BE833D8B9F4CE8564B9BE6C5CC011E00D5C001098F11A232080391521E4799FC5BB3EE1A8C010A00AE256F4963B33391DEE57DA748F5DCC011D00461A4FDC823C900659387DA00A49F5226A54EC378615002A47B364921C201236803349B856119B34C76BD8FB50B6C266EACE400424883880513B62687F38A13BCBEF127782A600B7002A923D4F959A0C94F740A969D0B4C016D00540010B8B70E226080331961C411950F3004F001579BA884DD45A59B40005D8362011C7198C4D0A4B8F73F3348AE40183CC7C86C017997F9BC6A35C220001BD367D08080287914B984D9A46932699675006A702E4E3BCF9EA5EE32600ACBEADC1CD00466446644A6FBC82F9002B734331D261F08020192459B24937D9664200B427963801A094A41CE529075200D5F4013988529EF82CEFED3699F469C8717E6675466007FE67BE815C9E84E2F300257224B256139A9E73637700B6334C63719E71D689B5F91F7BFF9F6EE33D5D72BE210013BCC01882111E31980391423FC4920042E39C7282E4028480021111E1BC6310066374638B200085C2C8DB05540119D229323700924BE0F3F1B527D89E4DB14AD253BFC30C01391F815002A539BA9C4BADB80152692A012CDCF20F35FDF635A9CCC71F261A080356B00565674FBE4ACE9F7C95EC19080371A009025B59BE05E5B59BE04E69322310020724FD3832401D14B4A34D1FE80233578CD224B9181F4C729E97508C017E005F2569D1D92D894BFE76FAC4C5FDDBA990097B2FBF704B40111006A1FC43898200E419859079C00C7003900B8D1002100A49700340090A40216CC00F1002900688201775400A3002C8040B50035802CC60087CC00E1002A4F35815900903285B401AA880391E61144C0004363445583A200CC2C939D3D1A41C66EC40";
-        println!("Day 16 Part 1 - {}", part1(parse_hexstring(input)));
+        let input = parse_puzzle("day16.txt").unwrap();
+        println!("Day 16 Part 1 - {}", part1(input));
     }
 
     #[test]
     fn example_day16_part2() {
-        assert_eq!(3, part2(parse_hexstring("C200B40A82")));
-        assert_eq!(0, part2(parse_hexstring("F600BC2D8F")));
-        assert_eq!(0, part2(parse_hexstring("9C005AC2F8F0")));
-        assert_eq!(1, part2(parse_hexstring("9C0141080250320F1802104A08")));
+        assert_eq!(BigUint::from(3u32), part2(from_str("C200B40A82").unwrap()));
+        assert_eq!(BigUint::from(0u32), part2(from_str("F600BC2D8F").unwrap()));
+        assert_eq!(
+            BigUint::from(0u32),
+            part2(from_str("9C005AC2F8F0").unwrap())
+        );
+        assert_eq!(
+            BigUint::from(1u32),
+            part2(from_str("9C0141080250320F1802104A08").unwrap())
+        );
     }
 
     #[test]
     fn exec_day16_part2() {
-        let input = "420D4900B8F31EFE7BD9DA455401AB80021504A2745E1007A21C1C862801F54AD0765BE833D8B9F4CE8564B9BE6C5CC011E00D5C001098F11A232080391521E4799FC5BB3EE1A8C010A00AE256F4963B33391DEE57DA748F5DCC011D00461A4FDC823C900659387DA00A49F5226A54EC378615002A47B364921C201236803349B856119B34C76BD8FB50B6C266EACE400424883880513B62687F38A13BCBEF127782A600B7002A923D4F959A0C94F740A969D0B4C016D00540010B8B70E226080331961C411950F3004F001579BA884DD45A59B40005D8362011C7198C4D0A4B8F73F3348AE40183CC7C86C017997F9BC6A35C220001BD367D08080287914B984D9A46932699675006A702E4E3BCF9EA5EE32600ACBEADC1CD00466446644A6FBC82F9002B734331D261F08020192459B24937D9664200B427963801A094A41CE529075200D5F4013988529EF82CEFED3699F469C8717E6675466007FE67BE815C9E84E2F300257224B256139A9E73637700B6334C63719E71D689B5F91F7BFF9F6EE33D5D72BE210013BCC01882111E31980391423FC4920042E39C7282E4028480021111E1BC6310066374638B200085C2C8DB05540119D229323700924BE0F3F1B527D89E4DB14AD253BFC30C01391F815002A539BA9C4BADB80152692A012CDCF20F35FDF635A9CCC71F261A080356B00565674FBE4ACE9F7C95EC19080371A009025B59BE05E5B59BE04E69322310020724FD3832401D14B4A34D1FE80233578CD224B9181F4C729E97508C017E005F2569D1D92D894BFE76FAC4C5FDDBA990097B2FBF704B40111006A1FC43898200E419859079C00C7003900B8D1002100A49700340090A40216CC00F1002900688201775400A3002C8040B50035802CC60087CC00E1002A4F35815900903285B401AA880391E61144C0004363445583A200CC2C939D3D1A41C66EC40";
-        println!("Day 16 Part 2 - {}", part2(parse_hexstring(input)));
+        let input = parse_puzzle("day16.txt").unwrap();
+        println!("Day 16 Part 2 - {}", part2(input));
     }
 }
@@ -1,6 +1,10 @@
 use std::{ops::{RangeInclusive}};
 
 use itertools::Itertools;
+use regex::{Captures, Regex};
+
+use crate::util::{column_of, parse_field, parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
 
 #[derive(Debug, Clone)]
 pub struct Probe {
@@ -38,50 +42,217 @@ impl Probe {
   }
 }
 
-pub fn execute1((dx, dy): (i32, i32), x_target: &RangeInclusive<i32>, y_target: &RangeInclusive<i32>) -> Option<i32> {
+// The smallest speed that covers `distance` before drag stops it, since a
+// speed of `v` travels at most the triangular number `v * (v + 1) / 2`.
+fn min_speed(distance: i32) -> i32 {
+  (0..).find(|v| v * (v + 1) / 2 >= distance).unwrap()
+}
+
+/// The launch velocities that could possibly reach the target, as ranges
+/// of `dx` and `dy`. `None` if the target spans y = 0, since a shot that
+/// stalls over it would then come back down into it however high it went.
+pub fn velocity_bounds(x_target: &RangeInclusive<i32>, y_target: &RangeInclusive<i32>) -> Option<(RangeInclusive<i32>, RangeInclusive<i32>)> {
+  let (x_min, x_max) = (*x_target.start(), *x_target.end());
+  let (y_min, y_max) = (*y_target.start(), *y_target.end());
+  if y_target.contains(&0) {
+    return None
+  }
+  // Anything faster than the far edge overshoots it on the first step, and
+  // anything slower than `min_speed` stalls before the near edge.
+  let dx = if x_min > 0 {
+    min_speed(x_min)..=x_max
+  } else if x_max < 0 {
+    x_min..=-min_speed(-x_max)
+  } else {
+    x_min..=x_max
+  };
+  // A shot upwards comes back down through y = 0 at speed `dy + 1`, so it
+  // passes a target below in one step unless `dy + 1 <= -y_min`. One above
+  // has to peak at least as high as its bottom edge.
+  let dy = if y_min > 0 {
+    min_speed(y_min)..=y_max
+  } else {
+    y_min..=y_max.max(-y_min - 1)
+  };
+  Some((dx, dy))
+}
+
+/// How high a probe launched at `(dx, dy)` climbs, if it ever lands in the
+/// target.
+pub fn launch((dx, dy): (i32, i32), x_target: &RangeInclusive<i32>, y_target: &RangeInclusive<i32>) -> Option<i32> {
   let mut max_y = 0;
-  for probe in (Probe { x: 0, y: 0, dx, dy }).iter()  {
-    let in_space = (0..200).contains(&probe.x) && (-100000..100000).contains(&probe.y);
-    let in_target = x_target.contains(&probe.x) && y_target.contains(&probe.y);
+  for probe in (Probe { x: 0, y: 0, dx, dy }).iter() {
     max_y = max_y.max(probe.y);
-    if !in_space {
-      return None
-    } else if in_target {
+    if x_target.contains(&probe.x) && y_target.contains(&probe.y) {
       return Some(max_y)
     }
+    let falling_past = probe.y < *y_target.start() && probe.dy < 0;
+    let drifting_past = match probe.dx.signum() {
+      1 => probe.x > *x_target.end(),
+      -1 => probe.x < *x_target.start(),
+      _ => !x_target.contains(&probe.x),
+    };
+    if falling_past || drifting_past {
+      return None
+    }
   }
   None
 }
 
+fn hits(x_target: RangeInclusive<i32>, y_target: RangeInclusive<i32>) -> impl Iterator<Item = i32> {
+  let (dx, dy) = velocity_bounds(&x_target, &y_target).expect("the target spans y = 0");
+  dx.cartesian_product(dy).flat_map(move |v| launch(v, &x_target, &y_target))
+}
+
 pub fn part1(x_target: RangeInclusive<i32>, y_target: RangeInclusive<i32>) -> i32 {
-  (0..10000).cartesian_product(0..10000).flat_map(|v| execute1(v, &x_target, &y_target)).max().unwrap()
+  hits(x_target, y_target).max().expect("no launch reaches the target")
 }
 
 pub fn part2(x_target: RangeInclusive<i32>, y_target: RangeInclusive<i32>) -> usize {
-  (-1000..1000).cartesian_product(-1000..1000).flat_map(|v| execute1(v, &x_target, &y_target)).count()
+  hits(x_target, y_target).count()
+}
+
+lazy_static! {
+  static ref TARGET_REGEX: Regex = Regex::new(
+    r"^target area: x=([-0-9]+)\.\.([-0-9]+), y=([-0-9]+)\.\.([-0-9]+)$"
+  )
+  .unwrap();
+}
+
+type Target = (RangeInclusive<i32>, RangeInclusive<i32>);
+
+fn make_range(line: &str, captures: &Captures, first_index: usize) -> Result<RangeInclusive<i32>, ParseError> {
+  let lower: i32 = parse_field(line, &captures[first_index], "a number")?;
+  let upper = parse_field(line, &captures[first_index + 1], "a number")?;
+  if lower > upper {
+    let range = &line[captures.get(first_index).unwrap().start()..captures.get(first_index + 1).unwrap().end()];
+    return Err(ParseError::new(column_of(line, range), range, "a range from low to high"));
+  }
+  Ok(lower..=upper)
+}
+
+pub fn parse_target(line: &str) -> Result<Target, ParseError> {
+  let captures = TARGET_REGEX
+    .captures(line)
+    .ok_or_else(|| ParseError::new(1, line, "`target area: x=A..B, y=C..D`"))?;
+  let x_target = make_range(line, &captures, 1)?;
+  let y_target = make_range(line, &captures, 3)?;
+  if y_target.contains(&0) {
+    let range = &line[captures.get(3).unwrap().start()..];
+    return Err(ParseError::new(column_of(line, range), range, "a y range that does not include 0"));
+  }
+  Ok((x_target, y_target))
+}
+
+pub fn from_str(input: &str) -> Result<Target, ParseError> {
+  parse_target(input.trim_end())
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Target, InputError> {
+  parse_file(file, from_str)
+}
+
+pub struct Day17;
+
+impl Solver for Day17 {
+  type Input = Target;
+  type Answer1 = i32;
+  type Answer2 = usize;
+
+  fn parse(input: &str) -> Result<Self::Input, ParseError> {
+    from_str(input)
+  }
+
+  fn part1((x_target, y_target): &Self::Input) -> Result<i32, SolveError> {
+    Ok(part1(x_target.clone(), y_target.clone()))
+  }
+
+  fn part2((x_target, y_target): &Self::Input) -> Result<usize, SolveError> {
+    Ok(part2(x_target.clone(), y_target.clone()))
+  }
 }
 
 #[cfg(test)]
 mod test {
   use super::*;
 
+  const EXAMPLE: &str = "target area: x=20..30, y=-10..-5\n";
+
+  #[test]
+  pub fn test_parse_target() {
+    assert_eq!((20..=30, -10..=-5), from_str(EXAMPLE).unwrap());
+    assert_eq!((48..=70, -189..=-148), parse_puzzle("day17.txt").unwrap());
+  }
+
+  #[test]
+  pub fn test_parse_target_error() {
+    assert_eq!(
+      ParseError::new(1, "target x=1..2, y=3..4", "`target area: x=A..B, y=C..D`"),
+      parse_target("target x=1..2, y=3..4").unwrap_err()
+    );
+    assert_eq!(
+      ParseError::new(16, "30..20", "a range from low to high"),
+      parse_target("target area: x=30..20, y=-10..-5").unwrap_err()
+    );
+    assert_eq!(
+      ParseError::new(26, "-99999999999", "a number"),
+      parse_target("target area: x=20..30, y=-99999999999..-5").unwrap_err()
+    );
+    assert_eq!(
+      ParseError::new(24, "-3..3", "a y range that does not include 0"),
+      parse_target("target area: x=5..9, y=-3..3").unwrap_err()
+    );
+    assert_eq!(None, velocity_bounds(&(5..=9), &(-3..=3)));
+    assert_eq!(None, velocity_bounds(&(5..=9), &(-3..=0)));
+  }
+
   #[test]
   pub fn examples_day17_part1() {
-    assert_eq!(45, part1(20..=30, -10..=-5));
+    let (x_target, y_target) = from_str(EXAMPLE).unwrap();
+    assert_eq!(45, part1(x_target, y_target));
   }
 
   #[test]
   pub fn exec_day17_part1() {
-    println!("Day 17 Part 1 - {}", part1(48..=70, -189..=-148));
+    let (x_target, y_target) = parse_puzzle("day17.txt").unwrap();
+    println!("Day 17 Part 1 - {}", part1(x_target, y_target));
   }
 
   #[test]
   pub fn examples_day17_part2() {
-    assert_eq!(112, part2(20..=30, -10..=-5));
+    let (x_target, y_target) = from_str(EXAMPLE).unwrap();
+    assert_eq!(112, part2(x_target, y_target));
   }
 
   #[test]
   pub fn exec_day17_part2() {
-    println!("Day 17 Part 2 - {}", part2(48..=70, -189..=-148));
+    let (x_target, y_target) = parse_puzzle("day17.txt").unwrap();
+    println!("Day 17 Part 2 - {}", part2(x_target, y_target));
+  }
+
+  #[test]
+  pub fn test_mirrored_targets() {
+    assert_eq!(45, part1(-30..=-20, -10..=-5));
+    assert_eq!(112, part2(-30..=-20, -10..=-5));
+  }
+
+  // Tries every velocity in a wide square, for checking the bounds against.
+  fn brute_force(x_target: RangeInclusive<i32>, y_target: RangeInclusive<i32>) -> Vec<i32> {
+    (-40..=40).cartesian_product(-40..=40).flat_map(|(dx, dy)| {
+      let mut max_y = 0;
+      (Probe { x: 0, y: 0, dx, dy }).iter().take(200).find_map(|probe| {
+        max_y = max_y.max(probe.y);
+        (x_target.contains(&probe.x) && y_target.contains(&probe.y)).then_some(max_y)
+      })
+    }).collect()
+  }
+
+  #[test]
+  pub fn test_bounds_against_brute_force() {
+    for (x_target, y_target) in [(20..=30, -10..=-5), (5..=9, 3..=8), (-9..=-5, 3..=8), (-3..=4, -6..=-2), (-4..=6, 2..=5)] {
+      let expected = brute_force(x_target.clone(), y_target.clone());
+      assert_eq!(expected.iter().max().copied(), hits(x_target.clone(), y_target.clone()).max());
+      assert_eq!(expected.len(), part2(x_target, y_target));
+    }
   }
 }
\ No newline at end of file
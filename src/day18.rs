@@ -1,49 +1,92 @@
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
 use itertools::Itertools;
 
+use crate::util::{lines, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
+
+type Chars<'a> = Peekable<CharIndices<'a>>;
+
+// Puzzle input is already reduced, so no pair is nested inside four others.
+// That leaves `add` room for the one extra level `explode` expects.
+const MAX_NESTING: u8 = 4;
+
+// Consumes `expected`, or reports whatever is there instead.
+fn expect(input: &str, chars: &mut Chars, expected: char) -> Result<(), ParseError> {
+    let expected_text = format!("`{}`", expected);
+    match chars.next() {
+        Some((_, c)) if c == expected => Ok(()),
+        Some((i, c)) => Err(ParseError::new(i + 1, &c.to_string(), &expected_text)),
+        None => Err(ParseError::new(input.len() + 1, "", &expected_text)),
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Number {
-    values: Vec<u8>,
+    // Reducing only moves values around, so however a sum of parsed numbers
+    // reduces, these stay far below overflowing.
+    values: Vec<u32>,
     depth: Vec<u8>,
 }
 
 impl Number {
-    fn parse(input: &str) -> Number {
-        let mut values = vec![];
-        let mut depths = vec![];
-        let mut building = false;
-        let mut acc = 0;
-        let mut depth = 0;
-        for c in input.chars() {
-            match c {
-                '[' => {
-                    depth += 1;
-                }
-                ']' => {
-                    if building {
-                        values.push(acc);
-                        depths.push(depth - 1);
-                    }
-                    building = false;
-                    acc = 0;
-                    depth -= 1;
-                }
-                ',' => {
-                    if building {
-                        values.push(acc);
-                        depths.push(depth - 1);
+    /// Parses a pair like `[[1,2],3]`. Its elements are either pairs or
+    /// numbers from 0 to 255.
+    fn parse(input: &str) -> Result<Number, ParseError> {
+        let mut number = Number {
+            values: vec![],
+            depth: vec![],
+        };
+        let mut chars = input.char_indices().peekable();
+        number.parse_pair(input, &mut chars, 0)?;
+        if let Some((i, _)) = chars.next() {
+            return Err(ParseError::new(i + 1, &input[i..], "the end of the line"));
+        }
+        Ok(number)
+    }
+
+    // Parses `[a,b]`, whose elements are nested `depth` pairs deep.
+    fn parse_pair(&mut self, input: &str, chars: &mut Chars, depth: u8) -> Result<(), ParseError> {
+        expect(input, chars, '[')?;
+        self.parse_element(input, chars, depth)?;
+        expect(input, chars, ',')?;
+        self.parse_element(input, chars, depth)?;
+        expect(input, chars, ']')
+    }
+
+    fn parse_element(
+        &mut self,
+        input: &str,
+        chars: &mut Chars,
+        depth: u8,
+    ) -> Result<(), ParseError> {
+        match chars.peek().copied() {
+            Some((i, '[')) if depth + 1 >= MAX_NESTING => Err(ParseError::new(
+                i + 1,
+                "[",
+                &format!("at most {} nested pairs", MAX_NESTING),
+            )),
+            Some((_, '[')) => self.parse_pair(input, chars, depth + 1),
+            Some((start, c)) if c.is_ascii_digit() => {
+                let mut end = start;
+                while let Some((i, c)) = chars.peek().copied() {
+                    if !c.is_ascii_digit() {
+                        break;
                     }
-                    building = false;
-                    acc = 0;
-                }
-                _ => {
-                    building = true;
-                    acc = acc * 10 + c.to_digit(10).unwrap() as u8;
+                    end = i + 1;
+                    chars.next();
                 }
+                let digits = &input[start..end];
+                let value: u8 = digits
+                    .parse()
+                    .map_err(|_| ParseError::new(start + 1, digits, "a number from 0 to 255"))?;
+                self.values.push(value.into());
+                self.depth.push(depth);
+                Ok(())
             }
-        }
-        Number {
-            values,
-            depth: depths,
+            Some((i, c)) => Err(ParseError::new(i + 1, &c.to_string(), "a number or `[`")),
+            None => Err(ParseError::new(input.len() + 1, "", "a number or `[`")),
         }
     }
 
@@ -110,14 +153,12 @@ impl Number {
     }
 
     fn reduce(&self) -> Number {
-        loop {
-            if let Some(new_value) = self.explode() {
-                return new_value.reduce();
-            } else if let Some(new_value) = self.split() {
-                return new_value.reduce();
-            } else {
-                return self.clone();
-            }
+        if let Some(new_value) = self.explode() {
+            new_value.reduce()
+        } else if let Some(new_value) = self.split() {
+            new_value.reduce()
+        } else {
+            self.clone()
         }
     }
 
@@ -140,25 +181,76 @@ impl Number {
     }
 }
 
-fn part2(input: Vec<Number>) -> u64 {
-  input.iter().cartesian_product(input.iter()).map(|(m,n)| {
-    if m != n {
-      m.add(n).reduce().magnitude()
-    } else {
-      0
+impl FromStr for Number {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Number, ParseError> {
+        Number::parse(input)
+    }
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Vec<Number>, InputError> {
+    parse_file(file, from_str)
+}
+
+pub fn from_str(input: &str) -> Result<Vec<Number>, ParseError> {
+    let numbers = try_parse_lines(lines(input), Number::parse)?;
+    if numbers.is_empty() {
+        return Err(ParseError::new(1, "", "`[`"));
+    }
+    Ok(numbers)
+}
+
+pub fn part1(input: Vec<Number>) -> u64 {
+    input
+        .into_iter()
+        .reduce(|m, n| m.add(&n).reduce())
+        .unwrap()
+        .magnitude()
+}
+
+pub fn part2(input: Vec<Number>) -> u64 {
+    input
+        .iter()
+        .cartesian_product(input.iter())
+        .map(|(m, n)| {
+            if m != n {
+                m.add(n).reduce().magnitude()
+            } else {
+                0
+            }
+        })
+        .max()
+        .unwrap()
+}
+
+pub struct Day18;
+
+impl Solver for Day18 {
+    type Input = Vec<Number>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, SolveError> {
+        Ok(part2(input.clone()))
     }
-  }).max().unwrap()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::util::read_file;
-
     use super::*;
 
     #[test]
     fn test_parser() {
-        let n = Number::parse("[[[[0,7],4],[15,[0,13]]],[1,1]]");
+        let n = Number::parse("[[[[0,7],4],[15,[0,13]]],[1,1]]").unwrap();
         assert_eq!(
             Number {
                 values: vec![0, 7, 4, 15, 0, 13, 1, 1],
@@ -166,7 +258,41 @@ mod test {
             },
             n
         );
-        dbg!(Number::parse("[[1,2],[[3,4],5]]"));
+        dbg!(Number::parse("[[1,2],[[3,4],5]]").unwrap());
+    }
+
+    #[test]
+    fn test_parser_error() {
+        assert_eq!(
+            ParseError::new(4, "x", "a number or `[`"),
+            Number::parse("[1,x]").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(7, "", "`,`"),
+            Number::parse("[[1,2]").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(5, ",", "`]`"),
+            Number::parse("[1,2,3]").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "1", "`[`"),
+            Number::parse("1").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(2, "300", "a number from 0 to 255"),
+            Number::parse("[300,1]").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(6, "]", "the end of the line"),
+            Number::parse("[1,2]]").unwrap_err()
+        );
+        assert!(Number::parse("[[[[1,2],0],0],0]").is_ok());
+        assert_eq!(
+            ParseError::new(5, "[", "at most 4 nested pairs"),
+            Number::parse("[[[[[1,2],0],0],0],0]").unwrap_err()
+        );
+        assert_eq!(ParseError::new(1, "", "`[`"), from_str("").unwrap_err());
     }
 
     #[test]
@@ -219,50 +345,46 @@ mod test {
 
     #[test]
     fn test_reduce() {
-        let input = Number::parse("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
-        dbg!(input.reduce());
+        let left = Number::parse("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap();
+        let right = Number::parse("[1,1]").unwrap();
+        assert_eq!(
+            Number::parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap(),
+            left.add(&right).reduce()
+        );
     }
 
     #[test]
     fn test_magnitude() {
-        assert_eq!(143, Number::parse("[[1,2],[[3,4],5]]").magnitude());
+        assert_eq!(143, Number::parse("[[1,2],[[3,4],5]]").unwrap().magnitude());
         assert_eq!(
             1137,
-            Number::parse("[[[[5,0],[7,4]],[5,5]],[6,6]]").magnitude()
+            Number::parse("[[[[5,0],[7,4]],[5,5]],[6,6]]")
+                .unwrap()
+                .magnitude()
         );
     }
 
     #[test]
     fn example_day18_part1() {
-        let numbers = read_file("day18.example")
-            .unwrap()
-            .map(|line| Number::parse(line.as_str()));
-        let answer = numbers.reduce(|m, n| m.add(&n).reduce()).unwrap();
-        assert_eq!(4140, answer.magnitude());
+        let numbers = parse_puzzle("day18.example").unwrap();
+        assert_eq!(4140, part1(numbers));
     }
 
     #[test]
     fn exec_day18_part1() {
-        let numbers = read_file("day18.txt")
-            .unwrap()
-            .map(|line| Number::parse(line.as_str()));
-        let answer = numbers.reduce(|m, n| m.add(&n).reduce()).unwrap();
-        println!("Day 18 Part 1 - {}", answer.magnitude());
+        let numbers = parse_puzzle("day18.txt").unwrap();
+        println!("Day 18 Part 1 - {}", part1(numbers));
     }
 
     #[test]
     fn example_day18_part2() {
-        let numbers = read_file("day18.example")
-            .unwrap()
-            .map(|line| Number::parse(line.as_str()));
-        assert_eq!(3993, part2(numbers.collect_vec()));
+        let numbers = parse_puzzle("day18.example").unwrap();
+        assert_eq!(3993, part2(numbers));
     }
 
     #[test]
     fn exec_day18_part2() {
-        let numbers = read_file("day18.txt")
-            .unwrap()
-            .map(|line| Number::parse(line.as_str()));
-        println!("Day 18 Part 2 - {}", part2(numbers.collect_vec()));
+        let numbers = parse_puzzle("day18.txt").unwrap();
+        println!("Day 18 Part 2 - {}", part2(numbers));
     }
 }
@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
+use crate::util::{lines, parse_field, parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
+
+pub type Point = [i32; 3];
+pub type Scanner = Vec<Point>;
+type Rotation = [[i32; 3]; 3];
+
+fn is_header(line: &str) -> bool {
+    line.strip_prefix("--- scanner ")
+        .and_then(|rest| rest.strip_suffix(" ---"))
+        .is_some_and(|number| number.parse::<usize>().is_ok())
+}
+
+pub fn parse_point(line: &str) -> Result<Point, ParseError> {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() != 3 {
+        return Err(ParseError::new(1, line, "a position like `x,y,z`"));
+    }
+    let mut point = [0; 3];
+    for (coordinate, part) in point.iter_mut().zip(parts) {
+        *coordinate = parse_field(line, part, "a number")?;
+    }
+    Ok(point)
+}
+
+pub fn from_str(input: &str) -> Result<Vec<Scanner>, ParseError> {
+    let mut scanners: Vec<Scanner> = vec![];
+    let mut in_scanner = false;
+    for (index, line) in lines(input).enumerate() {
+        let at_line = |e: ParseError| e.at_line(index + 1);
+        if line.is_empty() {
+            in_scanner = false;
+        } else if !in_scanner {
+            if !is_header(&line) {
+                return Err(at_line(ParseError::new(1, &line, "`--- scanner N ---`")));
+            }
+            scanners.push(vec![]);
+            in_scanner = true;
+        } else {
+            scanners
+                .last_mut()
+                .unwrap()
+                .push(parse_point(&line).map_err(at_line)?);
+        }
+    }
+    if scanners.is_empty() {
+        return Err(ParseError::new(1, "", "`--- scanner N ---`"));
+    }
+    Ok(scanners)
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Vec<Scanner>, InputError> {
+    parse_file(file, from_str)
+}
+
+// The 24 ways a scanner can face: signed permutations of the axes that keep
+// the coordinate system right-handed.
+fn rotations() -> Vec<Rotation> {
+    (0..3)
+        .permutations(3)
+        .cartesian_product((0..3).map(|_| [1, -1]).multi_cartesian_product())
+        .map(|(axes, signs)| {
+            let mut rotation = [[0; 3]; 3];
+            for row in 0..3 {
+                rotation[row][axes[row]] = signs[row];
+            }
+            rotation
+        })
+        .filter(|m| {
+            let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+                + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+            det == 1
+        })
+        .collect()
+}
+
+fn rotate(rotation: &Rotation, point: &Point) -> Point {
+    let mut result = [0; 3];
+    for (row, coordinate) in result.iter_mut().enumerate() {
+        *coordinate = (0..3).map(|col| rotation[row][col] * point[col]).sum();
+    }
+    result
+}
+
+// Finds the orientation and position, relative to `known`, at which at least
+// twelve of `scanner`'s beacons line up with it.
+fn align(known: &[Point], scanner: &[Point], rotations: &[Rotation]) -> Option<(Scanner, Point)> {
+    rotations.iter().find_map(|rotation| {
+        let rotated: Scanner = scanner.iter().map(|p| rotate(rotation, p)).collect();
+        let mut offsets: HashMap<Point, usize> = HashMap::new();
+        for a in known {
+            for b in &rotated {
+                *offsets
+                    .entry([a[0] - b[0], a[1] - b[1], a[2] - b[2]])
+                    .or_insert(0) += 1;
+            }
+        }
+        let (offset, _) = offsets.into_iter().find(|(_, count)| *count >= 12)?;
+        let moved = rotated
+            .iter()
+            .map(|p| [p[0] + offset[0], p[1] + offset[1], p[2] + offset[2]])
+            .collect();
+        Some((moved, offset))
+    })
+}
+
+/// Every beacon, and every scanner's position, relative to the first scanner.
+pub fn assemble(scanners: &[Scanner]) -> Option<(HashSet<Point>, Vec<Point>)> {
+    let rotations = rotations();
+    let mut placed: Vec<Option<(Scanner, Point)>> = vec![None; scanners.len()];
+    placed[0] = Some((scanners.first()?.clone(), [0, 0, 0]));
+    let mut frontier = vec![0];
+    while let Some(known) = frontier.pop() {
+        let known_beacons = placed[known].as_ref().unwrap().0.clone();
+        for index in 0..scanners.len() {
+            if placed[index].is_none() {
+                placed[index] = align(&known_beacons, &scanners[index], &rotations);
+                if placed[index].is_some() {
+                    frontier.push(index);
+                }
+            }
+        }
+    }
+    let placed: Vec<(Scanner, Point)> = placed.into_iter().collect::<Option<_>>()?;
+    let beacons = placed
+        .iter()
+        .flat_map(|(beacons, _)| beacons.clone())
+        .collect();
+    let positions = placed.into_iter().map(|(_, position)| position).collect();
+    Some((beacons, positions))
+}
+
+fn manhattan(a: &Point, b: &Point) -> i32 {
+    (0..3).map(|axis| (a[axis] - b[axis]).abs()).sum()
+}
+
+/// How many beacons there are, or `None` if the scanners cannot all be
+/// aligned.
+pub fn part1(input: Vec<Scanner>) -> Option<usize> {
+    Some(assemble(&input)?.0.len())
+}
+
+/// The largest distance between two scanners, or `None` if they cannot all
+/// be aligned.
+pub fn part2(input: Vec<Scanner>) -> Option<i32> {
+    let (_, positions) = assemble(&input)?;
+    Some(
+        positions
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| manhattan(a, b))
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+const UNALIGNED: &str = "the scanners cannot all be aligned";
+
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = Vec<Scanner>;
+    type Answer1 = usize;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        part1(input.clone()).ok_or_else(|| SolveError::no_answer(UNALIGNED))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i32, SolveError> {
+        part2(input.clone()).ok_or_else(|| SolveError::no_answer(UNALIGNED))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_puzzle() {
+        let input = parse_puzzle("day19.example").unwrap();
+        assert_eq!(5, input.len());
+        assert_eq!(25, input[0].len());
+    }
+
+    #[test]
+    fn test_parse_puzzle_error() {
+        assert_eq!(
+            ParseError::new(4, "1x", "a number").at_line(3),
+            from_str("--- scanner 0 ---\n1,2,3\n-1,1x,0").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "1,2,3", "`--- scanner N ---`"),
+            from_str("1,2,3").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "", "`--- scanner N ---`"),
+            from_str("").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_unaligned() {
+        let input = from_str("--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n4,5,6").unwrap();
+        assert_eq!(None, part1(input.clone()));
+        assert_eq!(Err(SolveError::no_answer(UNALIGNED)), Day19::part2(&input));
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotations = rotations();
+        assert_eq!(24, rotations.len());
+        let facings: HashSet<Point> = rotations.iter().map(|r| rotate(r, &[1, 2, 3])).collect();
+        assert_eq!(24, facings.len());
+    }
+
+    #[test]
+    fn example_day19_positions() {
+        let input = parse_puzzle("day19.example").unwrap();
+        let (_, positions) = assemble(&input).unwrap();
+        assert_eq!(
+            vec![
+                [0, 0, 0],
+                [68, -1246, -43],
+                [1105, -1205, 1229],
+                [-92, -2380, -20],
+                [-20, -1133, 1061]
+            ],
+            positions
+        );
+    }
+
+    #[test]
+    fn example_day19_part1() {
+        let input = parse_puzzle("day19.example").unwrap();
+        assert_eq!(Some(79), part1(input));
+    }
+
+    #[test]
+    fn example_day19_part2() {
+        let input = parse_puzzle("day19.example").unwrap();
+        assert_eq!(Some(3621), part2(input));
+    }
+}
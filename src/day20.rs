@@ -1,41 +1,73 @@
 use std::convert::TryInto;
+use std::str::FromStr;
 
 use itertools::Itertools;
-use ndarray::{Array2, Axis};
 
-use crate::util::read_file;
+use crate::util::grid::Grid;
+use crate::util::{expect_blank, lines, parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
 
 pub type Algorithm = Vec<bool>;
-pub type Image = Array2<bool>;
+pub type Image = Grid<bool>;
 
+#[derive(Clone, Debug)]
 pub struct Puzzle {
   algorithm: Algorithm,
   input: Image,
 }
 
-pub fn parse_puzzle(file: &str) -> Puzzle {
-  let mut lines = read_file(file).unwrap();
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+  parse_file(file, from_str)
+}
 
-  let algorithm = lines.next().unwrap().chars().map(|c| c == '#').collect_vec();
+impl FromStr for Puzzle {
+  type Err = ParseError;
 
-  lines.next();
+  fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    from_str(input)
+  }
+}
 
-  let image = lines.collect_vec();
-  let width = image[0].len();
-  let height = image.len();
+fn parse_pixel(c: char) -> Option<bool> {
+  match c {
+    '#' => Some(true),
+    '.' => Some(false),
+    _ => None,
+  }
+}
 
-  let input = Array2::from_shape_vec(
-    (width, height), 
-    image.iter().flat_map(|line| line.chars().collect_vec()).map(|c| c == '#').collect_vec()
-  ).unwrap();
+fn parse_pixels(line: &str) -> Result<Vec<bool>, ParseError> {
+  line.char_indices().map(|(i, c)| {
+    parse_pixel(c).ok_or_else(|| ParseError::new(i + 1, &c.to_string(), "`#` or `.`"))
+  }).collect()
+}
 
-  Puzzle { algorithm, input }
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+  let all_lines = lines(input).collect_vec();
+  let first_line = all_lines.first().cloned().unwrap_or_default();
+  let algorithm = parse_pixels(&first_line)?;
+  if algorithm.len() != 512 {
+    return Err(ParseError::new(1, &first_line, "an algorithm of 512 pixels"));
+  }
+
+  if let Some(separator) = all_lines.get(1) {
+    expect_blank(separator).map_err(|e| e.at_line(2))?;
+  }
+
+  // The image starts on the third line.
+  let image = all_lines.iter().skip(2).join("\n");
+  let input = Grid::parse(&image, parse_pixel, "`#` or `.`").map_err(|e| {
+    let line = e.line + 2;
+    e.at_line(line)
+  })?;
+
+  Ok(Puzzle { algorithm, input })
 }
 
 fn get_around(input: &Image, x: usize, y: usize, dx: i32, dy: i32) -> Option<bool> {
   let x: usize = ((x as i32) + dx).try_into().ok()?;
   let y: usize = ((y as i32) + dy).try_into().ok()?;
-  input.get((y, x)).copied()
+  input.get((x, y)).copied()
 }
 
 // target = 012345678
@@ -50,23 +82,18 @@ fn region(input: &Image, x: usize, y: usize, default: bool) -> usize {
 }
 
 pub fn decompress(image: Image, algorithm: &Algorithm, default: bool) -> Image {
-  if let [width, height] = image.shape() {
-    Array2::from_shape_fn(
-      (width + 2, height + 2),
-      | (y, x) | {
-        let region_value = region(&image, x, y, default);
-        algorithm[region_value]
-      },
-    )
-  } else {
-    panic!();
-  }
+  Grid::from_fn(
+    image.width() + 2,
+    image.height() + 2,
+    | (x, y) | {
+      let region_value = region(&image, x, y, default);
+      algorithm[region_value]
+    },
+  )
 }
 
 pub fn display(image: &Image) {
-  image.axis_iter(Axis(0)).for_each(|line| {
-    println!("{}", line.iter().map(|c| if *c { '#' } else {'.'}).join(""));
-  });
+  println!("{}", image.render(|c| if *c { '#' } else {'.'}));
   println!();
 }
 
@@ -82,8 +109,6 @@ pub fn part1(puzzle: Puzzle) -> usize {
     }
   }
 
-  display(&image);
-
   image.iter().filter(|pixel| **pixel).count()
 }
 
@@ -102,28 +127,52 @@ pub fn part2(puzzle: Puzzle) -> usize {
   image.iter().filter(|pixel| **pixel).count()
 }
 
+pub struct Day20;
+
+impl Solver for Day20 {
+  type Input = Puzzle;
+  type Answer1 = usize;
+  type Answer2 = usize;
+
+  fn parse(input: &str) -> Result<Self::Input, ParseError> {
+    input.parse()
+  }
+
+  fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+    Ok(part1(input.clone()))
+  }
+
+  fn part2(input: &Self::Input) -> Result<usize, SolveError> {
+    Ok(part2(input.clone()))
+  }
+}
+
 #[cfg(test)]
 mod test {
-  use ndarray::array;
-
   use super::*;
 
   #[test]
   fn test_parse_puzzle() {
-    let puzzle = parse_puzzle("day20.example");
+    let puzzle = parse_puzzle("day20.example").unwrap();
 
     assert_eq!(512, puzzle.algorithm.len());
-    assert_eq!(true, puzzle.algorithm[34]);
-    assert_eq!(true, puzzle.input[[0,0]]);
+    assert!(puzzle.algorithm[34]);
+    assert!(puzzle.input[(0,0)]);
+  }
+
+  #[test]
+  fn test_missing_separator() {
+    let input = format!("{}\n#.\n.#\n", ".".repeat(512));
+    assert_eq!(ParseError::new(1, "#.", "a blank line").at_line(2), from_str(&input).unwrap_err());
   }
 
   #[test]
   fn test_region() {
-    let image = array![
-      [false, false, false],
-      [true, false, false],
-      [false, true, false]
-    ];
+    let image = Grid::from_rows(vec![
+      vec![false, false, false],
+      vec![true, false, false],
+      vec![false, true, false]
+    ]).unwrap();
 
     assert_eq!(34, region(&image, 2, 2, false));
     assert_eq!(511, region(&image, 100, 100, true));
@@ -131,28 +180,28 @@ mod test {
 
   #[test]
   fn example_day20_part1() {
-    let puzzle = parse_puzzle("day20.example");
+    let puzzle = parse_puzzle("day20.example").unwrap();
 
     assert_eq!(35, part1(puzzle));
   }
 
   #[test]
   fn exec_day20_part1() {
-    let puzzle = parse_puzzle("day20.txt");
+    let puzzle = parse_puzzle("day20.txt").unwrap();
 
     println!("Day 20 Part 1 - {}", part1(puzzle));
   }
 
   #[test]
   fn example_day20_part2() {
-    let puzzle = parse_puzzle("day20.example");
+    let puzzle = parse_puzzle("day20.example").unwrap();
 
     assert_eq!(3351, part2(puzzle));
   }
 
   #[test]
   fn exec_day20_part2() {
-    let puzzle = parse_puzzle("day20.txt");
+    let puzzle = parse_puzzle("day20.txt").unwrap();
 
     println!("Day 20 Part 2 - {}", part2(puzzle));
   }
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use regex::Regex;
+
+use crate::util::{
+    column_of, lines, parse_field, parse_file, try_parse_lines, InputError, ParseError,
+};
+use crate::{SolveError, Solver};
+
+type Puzzle = (u32, u32);
+
+lazy_static! {
+    static ref START_REGEX: Regex = Regex::new(r"^Player [12] starting position: (\d+)$").unwrap();
+}
+
+pub fn parse_start(line: &str) -> Result<u32, ParseError> {
+    let captures = START_REGEX
+        .captures(line)
+        .ok_or_else(|| ParseError::new(1, line, "`Player N starting position: P`"))?;
+    let position = parse_field(line, &captures[1], "a position from 1 to 10")?;
+    if (1..=10).contains(&position) {
+        Ok(position)
+    } else {
+        Err(ParseError::new(
+            column_of(line, &captures[1]),
+            &captures[1],
+            "a position from 1 to 10",
+        ))
+    }
+}
+
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    let starts = try_parse_lines(lines(input), parse_start)?;
+    match starts.as_slice() {
+        [one, two] => Ok((*one, *two)),
+        _ => Err(ParseError::new(1, "", "two starting positions").at_line(starts.len() + 1)),
+    }
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
+}
+
+fn advance(position: u32, roll: u32) -> u32 {
+    (position + roll - 1) % 10 + 1
+}
+
+pub fn part1((one, two): Puzzle) -> u32 {
+    let mut die = (1..=100).cycle();
+    let mut rolls = 0;
+    let mut positions = [one, two];
+    let mut scores = [0, 0];
+    let mut player = 0;
+    loop {
+        let roll: u32 = die.by_ref().take(3).sum();
+        rolls += 3;
+        positions[player] = advance(positions[player], roll);
+        scores[player] += positions[player];
+        if scores[player] >= 1000 {
+            return scores[1 - player] * rolls;
+        }
+        player = 1 - player;
+    }
+}
+
+// How many of the 27 universes split off by three rolls of the Dirac die
+// land on each total.
+fn dirac_rolls() -> Vec<(u32, u64)> {
+    (0..3)
+        .map(|_| 1..=3)
+        .multi_cartesian_product()
+        .map(|rolls| rolls.iter().sum::<u32>())
+        .counts()
+        .into_iter()
+        .map(|(total, count)| (total, count as u64))
+        .collect()
+}
+
+type State = (u32, u32, u32, u32);
+
+// Wins for the player about to move, then for the other player.
+fn count_wins(
+    state: State,
+    rolls: &[(u32, u64)],
+    cache: &mut HashMap<State, (u64, u64)>,
+) -> (u64, u64) {
+    if let Some(wins) = cache.get(&state) {
+        return *wins;
+    }
+    let (position, score, other_position, other_score) = state;
+    let mut wins = (0, 0);
+    for (roll, universes) in rolls {
+        let position = advance(position, *roll);
+        let score = score + position;
+        if score >= 21 {
+            wins.0 += universes;
+        } else {
+            let (other_wins, my_wins) =
+                count_wins((other_position, other_score, position, score), rolls, cache);
+            wins.0 += universes * my_wins;
+            wins.1 += universes * other_wins;
+        }
+    }
+    cache.insert(state, wins);
+    wins
+}
+
+pub fn part2((one, two): Puzzle) -> u64 {
+    let (one_wins, two_wins) = count_wins((one, 0, two, 0), &dirac_rolls(), &mut HashMap::new());
+    one_wins.max(two_wins)
+}
+
+pub struct Day21;
+
+impl Solver for Day21 {
+    type Input = Puzzle;
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32, SolveError> {
+        Ok(part1(*input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, SolveError> {
+        Ok(part2(*input))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_puzzle() {
+        assert_eq!((4, 8), parse_puzzle("day21.example").unwrap());
+    }
+
+    #[test]
+    fn test_parse_puzzle_error() {
+        assert_eq!(
+            ParseError::new(29, "11", "a position from 1 to 10").at_line(2),
+            from_str("Player 1 starting position: 4\nPlayer 2 starting position: 11").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "", "two starting positions").at_line(2),
+            from_str("Player 1 starting position: 4\n").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn example_day21_part1() {
+        let input = parse_puzzle("day21.example").unwrap();
+        assert_eq!(739785, part1(input));
+    }
+
+    #[test]
+    fn example_day21_part2() {
+        let input = parse_puzzle("day21.example").unwrap();
+        assert_eq!(444356092776315, part2(input));
+    }
+}
@@ -1,10 +1,12 @@
-use std::{ops::{RangeInclusive, RangeBounds, Bound}, collections::HashSet};
+use std::{ops::RangeInclusive, collections::HashMap, str::FromStr};
 
 use itertools::Itertools;
 use regex::{Captures, Regex};
 
-use crate::util::read_lines;
+use crate::util::{lines, parse_field, parse_file, try_parse_lines, InputError, ParseError};
+use crate::{SolveError, Solver};
 
+#[derive(Clone, Debug)]
 pub struct Instruction {
     is_on: bool,
     x_range: RangeInclusive<i32>,
@@ -19,49 +21,139 @@ lazy_static! {
     .unwrap();
 }
 
-fn make_range(captures: &Captures, first_index: usize) -> Option<RangeInclusive<i32>> {
-    let lower = captures[first_index].parse().ok()?;
-    let upper = captures[first_index + 1].parse().ok()?;
-    Some(lower..=upper)
+fn make_range(
+    line: &str,
+    captures: &Captures,
+    first_index: usize,
+) -> Result<RangeInclusive<i32>, ParseError> {
+    let lower = parse_field(line, &captures[first_index], "a number")?;
+    let upper = parse_field(line, &captures[first_index + 1], "a number")?;
+    Ok(lower..=upper)
 }
 
-pub fn parse_puzzle(file: &str) -> impl Iterator<Item = Instruction> {
-    read_lines(file, |line| {
-        let captures = LINE_REGEX.captures(line.as_str())?;
-        Some(Instruction {
-            is_on: &captures[1] == "on",
-            x_range: make_range(&captures, 2)?,
-            y_range: make_range(&captures, 4)?,
-            z_range: make_range(&captures, 6)?,
-        })
+pub fn parse_instruction(line: &str) -> Result<Instruction, ParseError> {
+    let captures = LINE_REGEX.captures(line).ok_or_else(|| {
+        ParseError::new(1, line, "an instruction like `on x=1..2,y=3..4,z=5..6`")
+    })?;
+    Ok(Instruction {
+        is_on: &captures[1] == "on",
+        x_range: make_range(line, &captures, 2)?,
+        y_range: make_range(line, &captures, 4)?,
+        z_range: make_range(line, &captures, 6)?,
     })
-    .unwrap()
 }
 
-fn limit_range(input: RangeInclusive<i32>) -> RangeInclusive<i32> {
-  match (input.start_bound(), input.end_bound()) {
-    (Bound::Included(lower), Bound::Included(upper)) => {
-      *lower.max(&-50)..=*upper.min(&50)
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Instruction, ParseError> {
+        parse_instruction(line)
     }
-    _ => unimplemented!()
-  }
 }
 
-pub fn part1(input: impl Iterator<Item = Instruction>) -> usize {
-  let mut on_set = HashSet::new();
-  for Instruction {is_on, x_range, y_range, z_range} in input {
-    let x_range = limit_range(x_range);
-    let y_range = limit_range(y_range);
-    let z_range = limit_range(z_range);
-    for ((x, y), z) in x_range.cartesian_product(y_range).cartesian_product(z_range) {
-      if is_on {
-        on_set.insert((x, y, z));
-      } else {
-        on_set.remove(&(x, y, z));
+pub fn from_str(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    try_parse_lines(lines(input), parse_instruction)
+}
+
+pub fn parse_puzzle(file: &str) -> Result<impl Iterator<Item = Instruction>, InputError> {
+    Ok(parse_file(file, from_str)?.into_iter())
+}
+
+/// An axis-aligned box of cells, inclusive at both ends.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Cuboid {
+  min: [i32; 3],
+  max: [i32; 3],
+}
+
+impl Cuboid {
+  pub fn new(min: [i32; 3], max: [i32; 3]) -> Cuboid {
+    Cuboid { min, max }
+  }
+
+  pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+    let mut min = [0; 3];
+    let mut max = [0; 3];
+    for axis in 0..3 {
+      min[axis] = self.min[axis].max(other.min[axis]);
+      max[axis] = self.max[axis].min(other.max[axis]);
+      if min[axis] > max[axis] {
+        return None;
       }
     }
+    Some(Cuboid { min, max })
+  }
+
+  pub fn volume(&self) -> i64 {
+    (0..3).map(|axis| (self.max[axis] - self.min[axis]) as i64 + 1).product()
+  }
+}
+
+impl From<&Instruction> for Cuboid {
+  fn from(instruction: &Instruction) -> Cuboid {
+    let Instruction { x_range, y_range, z_range, .. } = instruction;
+    Cuboid {
+      min: [*x_range.start(), *y_range.start(), *z_range.start()],
+      max: [*x_range.end(), *y_range.end(), *z_range.end()],
+    }
+  }
+}
+
+// Every cuboid switched on adds its volume; every overlap with an earlier
+// cuboid is counted again with the opposite sign to cancel it out. Identical
+// cuboids share an entry so the list stays small.
+pub fn count_on(input: impl Iterator<Item = Instruction>, region: Option<Cuboid>) -> usize {
+  let mut signed: HashMap<Cuboid, i64> = HashMap::new();
+  for instruction in input {
+    let cuboid = Cuboid::from(&instruction);
+    let cuboid = match region {
+      Some(region) => match cuboid.intersection(&region) {
+        Some(cuboid) => cuboid,
+        None => continue,
+      },
+      None => cuboid,
+    };
+    let overlaps = signed
+      .iter()
+      .filter_map(|(other, sign)| Some((cuboid.intersection(other)?, -sign)))
+      .collect_vec();
+    for (overlap, sign) in overlaps {
+      *signed.entry(overlap).or_insert(0) += sign;
+    }
+    if instruction.is_on {
+      *signed.entry(cuboid).or_insert(0) += 1;
+    }
+    signed.retain(|_, sign| *sign != 0);
+  }
+  signed.iter().map(|(cuboid, sign)| cuboid.volume() * sign).sum::<i64>() as usize
+}
+
+pub fn part1(input: impl Iterator<Item = Instruction>) -> usize {
+  count_on(input, Some(Cuboid::new([-50; 3], [50; 3])))
+}
+
+pub fn part2(input: impl Iterator<Item = Instruction>) -> usize {
+  count_on(input, None)
+}
+
+pub struct Day22;
+
+impl Solver for Day22 {
+  type Input = Vec<Instruction>;
+  type Answer1 = usize;
+  type Answer2 = usize;
+
+  fn parse(input: &str) -> Result<Self::Input, ParseError> {
+    from_str(input)
+  }
+
+  fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+    Ok(part1(input.iter().cloned()))
+  }
+
+  fn part2(input: &Self::Input) -> Result<usize, SolveError> {
+    Ok(part2(input.iter().cloned()))
   }
-  on_set.len()
 }
 
 #[cfg(test)]
@@ -72,21 +164,57 @@ mod test {
 
     #[test]
     fn test_parse_puzzle() {
-        let input = parse_puzzle("day22.example").collect_vec();
+        let input = parse_puzzle("day22.example").unwrap().collect_vec();
         assert_eq!(22, input.len());
-        assert_eq!(true, input[0].is_on);
+        assert!(input[0].is_on);
         assert_eq!(967, input[21].x_range.clone().collect_vec()[0]);
     }
 
+    #[test]
+    fn test_parse_instruction_error() {
+        let error = parse_instruction("on x=-20..26,y=-36..17,z=-47..7a").unwrap_err();
+        assert_eq!(1, error.column);
+        let error = parse_instruction("on x=-20..26,y=-36..99999999999,z=-47..7").unwrap_err();
+        assert_eq!(ParseError::new(21, "99999999999", "a number"), error);
+    }
+
+    #[test]
+    fn test_cuboid() {
+      let a = Cuboid::new([10, 10, 10], [12, 12, 12]);
+      let b = Cuboid::new([11, 11, 11], [13, 13, 13]);
+      assert_eq!(27, a.volume());
+      assert_eq!(Some(Cuboid::new([11, 11, 11], [12, 12, 12])), a.intersection(&b));
+      assert_eq!(None, a.intersection(&Cuboid::new([13, 10, 10], [14, 12, 12])));
+    }
+
+    #[test]
+    fn small_example_day22() {
+      let input = from_str(
+        "on x=10..12,y=10..12,z=10..12\n\
+         on x=11..13,y=11..13,z=11..13\n\
+         off x=9..11,y=9..11,z=9..11\n\
+         on x=10..10,y=10..10,z=10..10",
+      )
+      .unwrap();
+      assert_eq!(39, part1(input.iter().cloned()));
+      assert_eq!(39, part2(input.into_iter()));
+    }
+
     #[test]
     fn example_day22_part1() {
-      let input = parse_puzzle("day22.example");
+      let input = parse_puzzle("day22.example").unwrap();
       assert_eq!(590784, part1(input));
     }
 
     #[test]
     fn exec_day22_part1() {
-      let input = parse_puzzle("day22.txt");
+      let input = parse_puzzle("day22.txt").unwrap();
       println!("Day 22 Part 1 - {}", part1(input));
     }
+
+    #[test]
+    fn exec_day22_part2() {
+      let input = parse_puzzle("day22.txt").unwrap();
+      println!("Day 22 Part 2 - {}", part2(input));
+    }
 }
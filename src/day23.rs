@@ -0,0 +1,245 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::util::{lines, parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
+
+const HALLWAY: usize = 11;
+const EMPTY: u8 = 4;
+const ENERGY: [usize; 4] = [1, 10, 100, 1000];
+
+/// The amphipods in each of the four side rooms, top first, as 0 for Amber
+/// up to 3 for Desert. The hallway always starts empty.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Burrow {
+    rooms: [Vec<u8>; 4],
+}
+
+fn entrance(room: usize) -> usize {
+    2 + 2 * room
+}
+
+fn expect_line(line_number: usize, line: &str, expected: &str) -> Result<(), ParseError> {
+    if line.trim_end() == expected {
+        Ok(())
+    } else {
+        Err(ParseError::new(1, line, &format!("`{}`", expected.trim())).at_line(line_number))
+    }
+}
+
+fn parse_room_line(line: &str) -> Result<[u8; 4], ParseError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut row = [0; 4];
+    for (room, slot) in row.iter_mut().enumerate() {
+        let column = 3 + 2 * room;
+        *slot = match chars.get(column) {
+            Some(c @ 'A'..='D') => *c as u8 - b'A',
+            Some(c) => return Err(ParseError::new(column + 1, &c.to_string(), "`A` to `D`")),
+            None => return Err(ParseError::new(column + 1, "", "`A` to `D`")),
+        };
+    }
+    Ok(row)
+}
+
+pub fn from_str(input: &str) -> Result<Burrow, ParseError> {
+    let all_lines: Vec<String> = lines(input).collect();
+    let line = |index: usize| all_lines.get(index).map_or("", |line| line.as_str());
+    expect_line(1, line(0), "#############")?;
+    expect_line(2, line(1), "#...........#")?;
+
+    let mut rooms: [Vec<u8>; 4] = Default::default();
+    let mut index = 2;
+    while line(index).trim() != "#########" {
+        let row = parse_room_line(line(index)).map_err(|e| e.at_line(index + 1))?;
+        for (room, amphipod) in rooms.iter_mut().zip(row.iter()) {
+            room.push(*amphipod);
+        }
+        index += 1;
+        if index >= all_lines.len() {
+            return Err(ParseError::new(1, "", "`#########`").at_line(index + 1));
+        }
+    }
+
+    let depth = rooms[0].len();
+    for kind in 0..4 {
+        let count = rooms.iter().flatten().filter(|&&a| a == kind).count();
+        if depth == 0 || count != depth {
+            let expected = format!("{} of each amphipod", depth.max(1));
+            let found = ((b'A' + kind) as char).to_string();
+            return Err(ParseError::new(1, &found, &expected).at_line(3));
+        }
+    }
+    Ok(Burrow { rooms })
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Burrow, InputError> {
+    parse_file(file, from_str)
+}
+
+/// The burrow with the two hidden rows from the folded-up diagram added.
+pub fn unfold(burrow: &Burrow) -> Burrow {
+    let hidden = [[3, 2, 1, 0], [3, 1, 0, 2]];
+    let mut rooms = burrow.rooms.clone();
+    for (room, amphipods) in rooms.iter_mut().enumerate() {
+        amphipods.splice(1..1, hidden.iter().map(|row| row[room]));
+    }
+    Burrow { rooms }
+}
+
+// A state is the hallway followed by each room, top first.
+type State = Vec<u8>;
+
+fn next_states(state: &[u8], depth: usize) -> Vec<(usize, State)> {
+    let mut next = vec![];
+    let slot = |room: usize, d: usize| HALLWAY + room * depth + d;
+    let clear = |from: usize, to: usize| {
+        let (low, high) = if from < to {
+            (from + 1, to)
+        } else {
+            (to, from - 1)
+        };
+        (low..=high).all(|h| state[h] == EMPTY)
+    };
+
+    // From the hallway straight into the amphipod's own room.
+    for h in 0..HALLWAY {
+        let amphipod = state[h];
+        if amphipod == EMPTY {
+            continue;
+        }
+        let room = amphipod as usize;
+        let contents = (0..depth).map(|d| state[slot(room, d)]);
+        if !contents.clone().all(|a| a == EMPTY || a == amphipod) || !clear(h, entrance(room)) {
+            continue;
+        }
+        let d = contents.take_while(|a| *a == EMPTY).count() - 1;
+        let steps = (h as isize - entrance(room) as isize).unsigned_abs() + d + 1;
+        let mut moved = state.to_vec();
+        moved[h] = EMPTY;
+        moved[slot(room, d)] = amphipod;
+        next.push((steps * ENERGY[room], moved));
+    }
+
+    // From the top of a room that still holds strangers out into the hallway.
+    for room in 0..4 {
+        let top = match (0..depth).find(|&d| state[slot(room, d)] != EMPTY) {
+            Some(top) => top,
+            None => continue,
+        };
+        if (top..depth).all(|d| state[slot(room, d)] == room as u8) {
+            continue;
+        }
+        let amphipod = state[slot(room, top)];
+        for h in (0..HALLWAY).filter(|h| ![2, 4, 6, 8].contains(h)) {
+            if !clear(entrance(room), h) || state[h] != EMPTY {
+                continue;
+            }
+            let steps = (h as isize - entrance(room) as isize).unsigned_abs() + top + 1;
+            let mut moved = state.to_vec();
+            moved[slot(room, top)] = EMPTY;
+            moved[h] = amphipod;
+            next.push((steps * ENERGY[amphipod as usize], moved));
+        }
+    }
+    next
+}
+
+/// The least energy needed to sort every amphipod into its own room.
+pub fn organise(burrow: &Burrow) -> Option<usize> {
+    let depth = burrow.rooms[0].len();
+    let mut start = vec![EMPTY; HALLWAY];
+    start.extend(burrow.rooms.iter().flatten());
+    let mut goal = vec![EMPTY; HALLWAY];
+    goal.extend((0..4).flat_map(|room| vec![room; depth]));
+
+    let mut best: HashMap<State, usize> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    best.insert(start.clone(), 0);
+    queue.push(Reverse((0, start)));
+    while let Some(Reverse((energy, state))) = queue.pop() {
+        if state == goal {
+            return Some(energy);
+        }
+        if best.get(&state).is_some_and(|&b| b < energy) {
+            continue;
+        }
+        for (cost, next) in next_states(&state, depth) {
+            let energy = energy + cost;
+            if best.get(&next).is_none_or(|&b| energy < b) {
+                best.insert(next.clone(), energy);
+                queue.push(Reverse((energy, next)));
+            }
+        }
+    }
+    None
+}
+
+pub fn part1(input: Burrow) -> usize {
+    organise(&input).unwrap()
+}
+
+pub fn part2(input: Burrow) -> usize {
+    organise(&unfold(&input)).unwrap()
+}
+
+pub struct Day23;
+
+impl Solver for Day23 {
+    type Input = Burrow;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part2(input.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_puzzle() {
+        let input = parse_puzzle("day23.example").unwrap();
+        assert_eq!(vec![1, 0], input.rooms[0]);
+        assert_eq!(vec![3, 0], input.rooms[3]);
+        assert_eq!(vec![1, 3, 3, 0], unfold(&input).rooms[0]);
+    }
+
+    #[test]
+    fn test_parse_puzzle_error() {
+        let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#E#A#\n  #########";
+        assert_eq!(
+            ParseError::new(8, "E", "`A` to `D`").at_line(4),
+            from_str(input).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_organise() {
+        let input =
+            from_str("#############\n#...........#\n###B#A#C#D###\n  #A#B#C#D#\n  #########")
+                .unwrap();
+        assert_eq!(Some(46), organise(&input));
+    }
+
+    #[test]
+    fn example_day23_part1() {
+        let input = parse_puzzle("day23.example").unwrap();
+        assert_eq!(12521, part1(input));
+    }
+
+    #[test]
+    fn example_day23_part2() {
+        let input = parse_puzzle("day23.example").unwrap();
+        assert_eq!(44169, part2(input));
+    }
+}
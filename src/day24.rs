@@ -0,0 +1,255 @@
+use crate::util::{
+    column_of, lines, parse_field, parse_file, try_parse_lines, InputError, ParseError,
+};
+use crate::{SolveError, Solver};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operand {
+    Register(usize),
+    Number(i64),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+type Program = Vec<Instruction>;
+
+fn parse_register(line: &str, part: &str) -> Result<usize, ParseError> {
+    match part {
+        "w" => Ok(0),
+        "x" => Ok(1),
+        "y" => Ok(2),
+        "z" => Ok(3),
+        _ => Err(ParseError::new(column_of(line, part), part, "a register")),
+    }
+}
+
+fn parse_operand(line: &str, part: &str) -> Result<Operand, ParseError> {
+    parse_register(line, part)
+        .map(Operand::Register)
+        .or_else(|_| parse_field(line, part, "a register or number").map(Operand::Number))
+}
+
+pub fn parse_instruction(line: &str) -> Result<Instruction, ParseError> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let missing = |expected| ParseError::new(line.len() + 1, "", expected);
+    let register = || parse_register(line, parts.get(1).ok_or_else(|| missing("a register"))?);
+    let operand = || {
+        parse_operand(
+            line,
+            parts
+                .get(2)
+                .ok_or_else(|| missing("a register or number"))?,
+        )
+    };
+    match parts.first() {
+        Some(&"inp") => Ok(Instruction::Inp(register()?)),
+        Some(&"add") => Ok(Instruction::Add(register()?, operand()?)),
+        Some(&"mul") => Ok(Instruction::Mul(register()?, operand()?)),
+        Some(&"div") => Ok(Instruction::Div(register()?, operand()?)),
+        Some(&"mod") => Ok(Instruction::Mod(register()?, operand()?)),
+        Some(&"eql") => Ok(Instruction::Eql(register()?, operand()?)),
+        Some(op) => Err(ParseError::new(
+            column_of(line, op),
+            op,
+            "an ALU instruction",
+        )),
+        None => Err(missing("an ALU instruction")),
+    }
+}
+
+pub fn from_str(input: &str) -> Result<Program, ParseError> {
+    try_parse_lines(lines(input), parse_instruction)
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Program, InputError> {
+    parse_file(file, from_str)
+}
+
+/// Runs `program` on `input`, returning the registers `w`, `x`, `y` and `z`,
+/// or `None` if it runs out of input or divides by zero.
+pub fn run(program: &[Instruction], input: &[i64]) -> Option<[i64; 4]> {
+    let mut registers = [0; 4];
+    let mut input = input.iter();
+    for instruction in program {
+        let value = |operand: Operand| match operand {
+            Operand::Register(r) => registers[r],
+            Operand::Number(n) => n,
+        };
+        match *instruction {
+            Instruction::Inp(a) => registers[a] = *input.next()?,
+            Instruction::Add(a, b) => registers[a] += value(b),
+            Instruction::Mul(a, b) => registers[a] *= value(b),
+            Instruction::Div(a, b) => registers[a] = registers[a].checked_div(value(b))?,
+            Instruction::Mod(a, b) => {
+                let b = value(b);
+                if registers[a] < 0 || b <= 0 {
+                    return None;
+                }
+                registers[a] %= b
+            }
+            Instruction::Eql(a, b) => registers[a] = (registers[a] == value(b)) as i64,
+        }
+    }
+    Some(registers)
+}
+
+// MONAD is fourteen copies of the same block, differing only in whether it
+// divides `z` by 1 or 26 and two constants. Returns those per block.
+fn monad_blocks(program: &[Instruction]) -> Option<Vec<(i64, i64, i64)>> {
+    use Instruction::*;
+    use Operand::*;
+    let (w, x, y, z) = (0, 1, 2, 3);
+    program
+        .chunks(18)
+        .map(|block| {
+            if block.len() != 18 {
+                return None;
+            }
+            let template = [
+                Inp(w),
+                Mul(x, Number(0)),
+                Add(x, Register(z)),
+                Mod(x, Number(26)),
+                block[4],
+                block[5],
+                Eql(x, Register(w)),
+                Eql(x, Number(0)),
+                Mul(y, Number(0)),
+                Add(y, Number(25)),
+                Mul(y, Register(x)),
+                Add(y, Number(1)),
+                Mul(z, Register(y)),
+                Mul(y, Number(0)),
+                Add(y, Register(w)),
+                block[15],
+                Mul(y, Register(x)),
+                Add(z, Register(y)),
+            ];
+            match (block == template, block[4], block[5], block[15]) {
+                (true, Div(3, Number(d)), Add(1, Number(a)), Add(2, Number(b))) => Some((d, a, b)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// Each block that divides by 1 pushes `w + b` onto `z` as a base-26 stack,
+// and each that divides by 26 pops it, so `z` only ends at zero if every
+// popping digit equals its pushing digit plus `b + a`.
+fn model_number(program: &[Instruction], largest: bool) -> Option<i64> {
+    let blocks = monad_blocks(program)?;
+    if blocks.len() != 14 {
+        return None;
+    }
+    let mut digits = [0; 14];
+    let mut stack = vec![];
+    for (i, &(d, a, b)) in blocks.iter().enumerate() {
+        if d == 1 {
+            stack.push((i, b));
+            continue;
+        }
+        let (j, b) = stack.pop()?;
+        let diff = b + a;
+        digits[j] = if largest {
+            9.min(9 - diff)
+        } else {
+            1.max(1 - diff)
+        };
+        digits[i] = digits[j] + diff;
+    }
+    if !stack.is_empty() || digits.iter().any(|d| !(1..=9).contains(d)) {
+        return None;
+    }
+    match run(program, &digits)? {
+        [_, _, _, 0] => Some(digits.iter().fold(0, |n, d| n * 10 + d)),
+        _ => None,
+    }
+}
+
+pub fn part1(input: Program) -> i64 {
+    model_number(&input, true).unwrap()
+}
+
+pub fn part2(input: Program) -> i64 {
+    model_number(&input, false).unwrap()
+}
+
+pub struct Day24;
+
+impl Solver for Day24 {
+    type Input = Program;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64, SolveError> {
+        Ok(part2(input.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_instruction() {
+        assert_eq!(
+            Instruction::Add(3, Operand::Number(-7)),
+            parse_instruction("add z -7").unwrap()
+        );
+        assert_eq!(
+            Instruction::Eql(1, Operand::Register(0)),
+            parse_instruction("eql x w").unwrap()
+        );
+        assert_eq!(
+            ParseError::new(5, "q", "a register"),
+            parse_instruction("mul q 2").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "jmp", "an ALU instruction"),
+            parse_instruction("jmp 3").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let negate = from_str("inp x\nmul x -1").unwrap();
+        assert_eq!(Some([0, -5, 0, 0]), run(&negate, &[5]));
+
+        let triple = from_str("inp z\ninp x\nmul z 3\neql z x").unwrap();
+        assert_eq!(1, run(&triple, &[2, 6]).unwrap()[3]);
+        assert_eq!(0, run(&triple, &[2, 7]).unwrap()[3]);
+
+        let binary = "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\n\
+                      div w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2";
+        assert_eq!(Some([1, 0, 1, 1]), run(&from_str(binary).unwrap(), &[11]));
+        assert_eq!(None, run(&negate, &[]));
+    }
+
+    #[test]
+    fn example_day24_part1() {
+        let input = parse_puzzle("day24.example").unwrap();
+        assert_eq!(97299996426999, part1(input));
+    }
+
+    #[test]
+    fn example_day24_part2() {
+        let input = parse_puzzle("day24.example").unwrap();
+        assert_eq!(81183641111114, part2(input));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::util::grid::Grid;
+use crate::util::{parse_file, InputError, ParseError};
+use crate::{SolveError, Solver};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+type Puzzle = Grid<Cell>;
+
+fn parse_cell(c: char) -> Option<Cell> {
+    match c {
+        '.' => Some(Cell::Empty),
+        '>' => Some(Cell::East),
+        'v' => Some(Cell::South),
+        _ => None,
+    }
+}
+
+pub fn from_str(input: &str) -> Result<Puzzle, ParseError> {
+    Grid::parse(input, parse_cell, "`.`, `>` or `v`")
+}
+
+pub fn parse_puzzle(file: &str) -> Result<Puzzle, InputError> {
+    parse_file(file, from_str)
+}
+
+// Moves every cucumber of one herd that can, all at once. Returns whether
+// any moved.
+fn move_herd(input: &mut Puzzle, herd: Cell) -> bool {
+    let (width, height) = (input.width(), input.height());
+    let (dx, dy) = match herd {
+        Cell::East => (1, 0),
+        _ => (0, 1),
+    };
+    let moves: Vec<_> = input
+        .points()
+        .filter(|&point| input[point] == herd)
+        .map(|(x, y)| ((x, y), ((x + dx) % width, (y + dy) % height)))
+        .filter(|&(_, next)| input[next] == Cell::Empty)
+        .collect();
+    for &(point, next) in &moves {
+        input[point] = Cell::Empty;
+        input[next] = herd;
+    }
+    !moves.is_empty()
+}
+
+pub fn step(input: &mut Puzzle) -> bool {
+    let east = move_herd(input, Cell::East);
+    let south = move_herd(input, Cell::South);
+    east || south
+}
+
+pub fn part1(mut input: Puzzle) -> usize {
+    let mut steps = 1;
+    while step(&mut input) {
+        steps += 1;
+    }
+    steps
+}
+
+// The last star comes free with the other 49.
+pub fn part2(_input: Puzzle) -> String {
+    "Merry Christmas!".to_string()
+}
+
+pub struct Day25;
+
+impl Solver for Day25 {
+    type Input = Puzzle;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        from_str(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, SolveError> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input) -> Result<String, SolveError> {
+        Ok(part2(input.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_puzzle() {
+        let input = parse_puzzle("day25.example").unwrap();
+        assert_eq!(9, input.height());
+        assert_eq!(10, input.width());
+        assert_eq!(Cell::South, input[(0, 0)]);
+        assert_eq!(Cell::East, input[(4, 0)]);
+    }
+
+    #[test]
+    fn test_parse_puzzle_error() {
+        assert_eq!(
+            ParseError::new(3, "x", "`.`, `>` or `v`").at_line(2),
+            from_str("..>\n.vx").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, ".v", "rows of equal width").at_line(2),
+            from_str("..>\n.v").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let mut input = from_str("...>>>>>...").unwrap();
+        step(&mut input);
+        assert_eq!(from_str("...>>>>.>..").unwrap(), input);
+        step(&mut input);
+        assert_eq!(from_str("...>>>.>.>.").unwrap(), input);
+    }
+
+    #[test]
+    fn example_day25_part1() {
+        let input = parse_puzzle("day25.example").unwrap();
+        assert_eq!(58, part1(input));
+    }
+}
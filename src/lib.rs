@@ -1,6 +1,11 @@
 #[macro_use]
 extern crate lazy_static;
 
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -19,7 +24,172 @@ pub mod day15;
 pub mod day16;
 pub mod day17;
 pub mod day18;
+pub mod day19;
 pub mod day20;
+pub mod day21;
 pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 
+pub mod answers;
+pub mod bench;
 pub mod util;
+
+use util::ParseError;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+/// Why a day could not be solved: either its input was malformed, or it
+/// parsed but has no answer for the part asked.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),
+    NoAnswer(String),
+}
+
+impl SolveError {
+    pub fn no_answer(reason: &str) -> SolveError {
+        SolveError::NoAnswer(reason.to_string())
+    }
+
+    pub fn in_file(self, file: &str) -> SolveError {
+        match self {
+            SolveError::Parse(error) => SolveError::Parse(error.in_file(file)),
+            SolveError::NoAnswer(_) => self,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(error: ParseError) -> SolveError {
+        SolveError::Parse(error)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Parse(error) => write!(f, "{}", error),
+            SolveError::NoAnswer(reason) => write!(f, "no answer: {}", reason),
+        }
+    }
+}
+
+// Like `ParseError`, so that `unwrap` failures read as the CLI's would.
+impl fmt::Debug for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolveError::Parse(error) => Some(error),
+            SolveError::NoAnswer(_) => None,
+        }
+    }
+}
+
+/// A day's puzzle: how to parse its input and answer both parts. Input that
+/// parses but has no answer, such as a bingo game nobody wins, is reported
+/// as [`SolveError::NoAnswer`] rather than a panic.
+pub trait Solver {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError>;
+}
+
+/// Object-safe view of a [`Solver`], so days can live side by side in the
+/// registry.
+pub trait Solution {
+    fn solve(&self, input: &str, part: Part) -> Result<String, SolveError>;
+
+    /// How long parsing, part 1 and part 2 each take on `input`, once.
+    fn time(&self, input: &str) -> Result<[Duration; 3], SolveError>;
+}
+
+impl<S: Solver> Solution for S {
+    fn solve(&self, input: &str, part: Part) -> Result<String, SolveError> {
+        let input = S::parse(input)?;
+        Ok(match part {
+            Part::One => S::part1(&input)?.to_string(),
+            Part::Two => S::part2(&input)?.to_string(),
+        })
+    }
+
+    fn time(&self, input: &str) -> Result<[Duration; 3], SolveError> {
+        let start = Instant::now();
+        let input = S::parse(input)?;
+        let parsed = Instant::now();
+        black_box(S::part1(&input)?);
+        let solved1 = Instant::now();
+        black_box(S::part2(&input)?);
+        let solved2 = Instant::now();
+        Ok([parsed - start, solved1 - parsed, solved2 - solved1])
+    }
+}
+
+pub fn solvers() -> BTreeMap<u32, Box<dyn Solution>> {
+    let mut solvers: BTreeMap<u32, Box<dyn Solution>> = BTreeMap::new();
+    solvers.insert(1, Box::new(day01::Day01));
+    solvers.insert(2, Box::new(day02::Day02));
+    solvers.insert(3, Box::new(day03::Day03));
+    solvers.insert(4, Box::new(day04::Day04));
+    solvers.insert(5, Box::new(day05::Day05));
+    solvers.insert(6, Box::new(day06::Day06));
+    solvers.insert(7, Box::new(day07::Day07));
+    solvers.insert(8, Box::new(day08::Day08));
+    solvers.insert(9, Box::new(day09::Day09));
+    solvers.insert(10, Box::new(day10::Day10));
+    solvers.insert(11, Box::new(day11::Day11));
+    solvers.insert(12, Box::new(day12::Day12));
+    solvers.insert(13, Box::new(day13::Day13));
+    solvers.insert(14, Box::new(day14::Day14));
+    solvers.insert(15, Box::new(day15::Day15));
+    solvers.insert(16, Box::new(day16::Day16));
+    solvers.insert(17, Box::new(day17::Day17));
+    solvers.insert(18, Box::new(day18::Day18));
+    solvers.insert(19, Box::new(day19::Day19));
+    solvers.insert(20, Box::new(day20::Day20));
+    solvers.insert(21, Box::new(day21::Day21));
+    solvers.insert(22, Box::new(day22::Day22));
+    solvers.insert(23, Box::new(day23::Day23));
+    solvers.insert(24, Box::new(day24::Day24));
+    solvers.insert(25, Box::new(day25::Day25));
+    solvers
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solvers() {
+        let solvers = solvers();
+        assert_eq!(25, solvers.len());
+        assert_eq!(
+            Ok("7".to_string()),
+            solvers[&1].solve(
+                "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n",
+                Part::One
+            )
+        );
+        assert_eq!(
+            "2:3: expected 0 or 1, found `2`",
+            solvers[&3]
+                .solve("00100\n11210\n", Part::One)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
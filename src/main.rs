@@ -0,0 +1,245 @@
+use std::env::args;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+use advent2021::bench::{bench, format_table};
+use advent2021::util::pathfinding::Algorithm;
+use advent2021::util::{input_root, read_input};
+use advent2021::{day15, day16, solvers, Part};
+
+const USAGE: &str = "usage: advent2021 run --day <DAY> --part <1|2> --input <PATH|-> [--root <DIR>]
+                       [--algorithm <dijkstra|astar|bidirectional>]
+       advent2021 bench [--day <DAY>] [--runs <N>] [--root <DIR>]
+       advent2021 disassemble --input <PATH|-> [--root <DIR>] [--format <tree|infix>]";
+
+struct Options {
+    day: u32,
+    part: Part,
+    input: String,
+    root: Option<PathBuf>,
+    algorithm: Option<Algorithm>,
+}
+
+struct BenchOptions {
+    day: Option<u32>,
+    runs: u32,
+    root: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Tree,
+    Infix,
+}
+
+struct DisassembleOptions {
+    input: String,
+    root: Option<PathBuf>,
+    format: Format,
+}
+
+enum Command {
+    Run(Options),
+    Bench(BenchOptions),
+    Disassemble(DisassembleOptions),
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => parse_run_args(args).map(Command::Run),
+        Some("bench") => parse_bench_args(args).map(Command::Bench),
+        Some("disassemble") => parse_disassemble_args(args).map(Command::Disassemble),
+        Some(other) => Err(format!("unknown command `{}`", other)),
+        None => Err("missing command".to_string()),
+    }
+}
+
+fn parse_day(value: &str) -> Result<u32, String> {
+    value.parse().map_err(|_| format!("bad day `{}`", value))
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut root = None;
+    let mut algorithm = None;
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for `{}`", flag))?;
+        match flag.as_str() {
+            "--day" => day = Some(parse_day(&value)?),
+            "--part" => {
+                part = Some(match value.as_str() {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    _ => return Err(format!("bad part `{}`", value)),
+                })
+            }
+            "--input" => input = Some(value),
+            "--root" => root = Some(PathBuf::from(value)),
+            "--algorithm" => algorithm = Some(value.parse()?),
+            _ => return Err(format!("unknown flag `{}`", flag)),
+        }
+    }
+
+    Ok(Options {
+        day: day.ok_or("missing --day")?,
+        part: part.ok_or("missing --part")?,
+        input: input.ok_or("missing --input")?,
+        root,
+        algorithm,
+    })
+}
+
+fn parse_bench_args(mut args: impl Iterator<Item = String>) -> Result<BenchOptions, String> {
+    let mut options = BenchOptions {
+        day: None,
+        runs: 10,
+        root: None,
+    };
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for `{}`", flag))?;
+        match flag.as_str() {
+            "--day" => options.day = Some(parse_day(&value)?),
+            "--runs" => {
+                options.runs = match value.parse() {
+                    Ok(runs) if runs > 0 => runs,
+                    _ => return Err(format!("bad number of runs `{}`", value)),
+                }
+            }
+            "--root" => options.root = Some(PathBuf::from(value)),
+            _ => return Err(format!("unknown flag `{}`", flag)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_disassemble_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<DisassembleOptions, String> {
+    let mut input = None;
+    let mut root = None;
+    let mut format = Format::Tree;
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for `{}`", flag))?;
+        match flag.as_str() {
+            "--input" => input = Some(value),
+            "--root" => root = Some(PathBuf::from(value)),
+            "--format" => {
+                format = match value.as_str() {
+                    "tree" => Format::Tree,
+                    "infix" => Format::Infix,
+                    _ => return Err(format!("bad format `{}`", value)),
+                }
+            }
+            _ => return Err(format!("unknown flag `{}`", flag)),
+        }
+    }
+    Ok(DisassembleOptions {
+        input: input.ok_or("missing --input")?,
+        root,
+        format,
+    })
+}
+
+// Where `--input` is, taking `--root` into account.
+fn input_path(input: &str, root: &Option<PathBuf>) -> PathBuf {
+    match root {
+        Some(root) if input != "-" => root.join(input),
+        _ => PathBuf::from(input),
+    }
+}
+
+fn run(options: Options) -> Result<String, String> {
+    let path = input_path(&options.input, &options.root);
+    let input =
+        read_input(&path).map_err(|e| format!("cannot read `{}`: {}", path.display(), e))?;
+
+    let solver = solvers()
+        .remove(&options.day)
+        .ok_or_else(|| format!("no solution for day {}", options.day))?;
+    let answer = match options.algorithm {
+        Some(algorithm) if options.day == 15 => day15::solve_with(&input, options.part, algorithm),
+        Some(_) => return Err("--algorithm only applies to day 15".to_string()),
+        None => solver.solve(&input, options.part),
+    };
+    answer.map_err(|e| e.in_file(&file_name(&path)).to_string())
+}
+
+// How to refer to an input path in errors.
+fn file_name(path: &Path) -> String {
+    if path == Path::new("-") {
+        "<stdin>".to_string()
+    } else {
+        path.to_string_lossy().into_owned()
+    }
+}
+
+// Times every registered day, or just `--day`, on `dayNN.txt` from the input
+// root. Days without an input file are skipped.
+fn run_bench(options: BenchOptions) -> Result<String, String> {
+    let root = options.root.unwrap_or_else(input_root);
+    let mut solvers = solvers();
+    if let Some(day) = options.day {
+        solvers.retain(|d, _| *d == day);
+        if solvers.is_empty() {
+            return Err(format!("no solution for day {}", day));
+        }
+    }
+
+    let mut rows = vec![];
+    for (day, solver) in solvers {
+        let path = root.join(format!("day{:02}.txt", day));
+        let input = match read_input(&path) {
+            Ok(input) => input,
+            Err(_) => {
+                eprintln!("skipping day {}: no input at `{}`", day, path.display());
+                continue;
+            }
+        };
+        let stats = bench(solver.as_ref(), &input, options.runs)
+            .map_err(|e| e.in_file(&path.to_string_lossy()).to_string())?;
+        rows.push((day, stats));
+    }
+    Ok(format_table(&rows).trim_end().to_string())
+}
+
+// Prints the day 16 transmission in `--input` as a tree or an expression.
+fn run_disassemble(options: DisassembleOptions) -> Result<String, String> {
+    let path = input_path(&options.input, &options.root);
+    let input =
+        read_input(&path).map_err(|e| format!("cannot read `{}`: {}", path.display(), e))?;
+    // Skip `from_str`'s checks, so that malformed operators can be seen.
+    let bytes = day16::parse_hexstring(input.trim())
+        .map_err(|e| e.in_file(&file_name(&path)).to_string())?;
+    let packet = day16::decode(&bytes).map_err(|e| e.to_string())?;
+    Ok(match options.format {
+        Format::Tree => day16::disassemble(&packet),
+        Format::Infix => packet.to_string(),
+    })
+}
+
+fn main() {
+    let command = parse_args(args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("error: {}\n{}", message, USAGE);
+        exit(2);
+    });
+    let result = match command {
+        Command::Run(options) => run(options),
+        Command::Bench(options) => run_bench(options),
+        Command::Disassemble(options) => run_disassemble(options),
+    };
+    match result {
+        Ok(answer) => println!("{}", answer),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            exit(1);
+        }
+    }
+}
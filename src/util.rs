@@ -1,45 +1,260 @@
-use std::env::current_dir;
+use std::env::var_os;
+use std::ffi::OsString;
+use std::fmt;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::{BufReader, Result};
-
-pub fn read_file(path: &str) -> Result<impl Iterator<Item = String>> {
-    let mut full_path = current_dir()?;
-    full_path.push("fixtures");
-    full_path.push(path);
-    let file = File::open(full_path)?;
-    Ok(BufReader::new(file).lines().map(|line| line.unwrap()))
-}
-
-pub fn read_line<T: std::str::FromStr>(path: &str) -> Vec<T> {
-    read_lines(path, move |line| {
-        Some(
-            line.split(',')
-                .map(|value| value.parse().ok().unwrap())
-                .collect(),
+use std::io::{self, stdin};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub mod grid;
+pub mod pathfinding;
+
+/// A malformed piece of puzzle input and where it was found. Lines and
+/// columns count from 1.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub found: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, found: &str, expected: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line: 1,
+            column,
+            found: found.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn at_line(self, line: usize) -> ParseError {
+        ParseError { line, ..self }
+    }
+
+    pub fn in_file(self, file: &str) -> ParseError {
+        ParseError {
+            file: Some(file.to_string()),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(
+            f,
+            "{}:{}: expected {}, found `{}`",
+            self.line, self.column, self.expected, self.found
         )
-    })
-    .unwrap()
-    .next()
-    .unwrap()
+    }
+}
+
+// `unwrap` reports errors with `Debug`, so make test failures read as nicely
+// as the CLI's.
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why a puzzle file could not be loaded: either it could not be read, or
+/// what it held was malformed.
+pub enum InputError {
+    Io { path: String, error: io::Error },
+    Parse(ParseError),
+}
+
+impl InputError {
+    pub fn io(path: &str, error: io::Error) -> InputError {
+        InputError::Io {
+            path: path.to_string(),
+            error,
+        }
+    }
+}
+
+impl From<ParseError> for InputError {
+    fn from(error: ParseError) -> InputError {
+        InputError::Parse(error)
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Io { path, error } => write!(f, "cannot read `{}`: {}", path, error),
+            InputError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+// Like `ParseError`, so that `unwrap` failures read as the CLI's would.
+impl fmt::Debug for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io { error, .. } => Some(error),
+            InputError::Parse(error) => Some(error),
+        }
+    }
+}
+
+/// The 1-based column at which `part`, a slice of `line`, starts.
+pub fn column_of(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Parses `part`, a slice of `line`, reporting its position on failure.
+pub fn parse_field<T: FromStr>(line: &str, part: &str, expected: &str) -> Result<T, ParseError> {
+    part.parse()
+        .map_err(|_| ParseError::new(column_of(line, part), part, expected))
+}
+
+/// Checks that a line separating two sections of a puzzle is blank.
+pub fn expect_blank(line: &str) -> Result<(), ParseError> {
+    if line.trim().is_empty() {
+        Ok(())
+    } else {
+        Err(ParseError::new(1, line, "a blank line"))
+    }
+}
+
+/// Environment variable that overrides where [`read_file`] looks for inputs.
+pub const INPUT_ROOT_VAR: &str = "ADVENT2021_INPUTS";
+
+/// The directory relative input paths are resolved against: `$ADVENT2021_INPUTS`
+/// if set, otherwise `fixtures/` in the working directory.
+pub fn input_root() -> PathBuf {
+    root_from(var_os(INPUT_ROOT_VAR))
+}
+
+fn root_from(var: Option<OsString>) -> PathBuf {
+    var.map_or_else(|| PathBuf::from("fixtures"), PathBuf::from)
+}
+
+/// Reads all of `path`, or standard input if `path` is `-`.
+pub fn read_input(path: &Path) -> io::Result<String> {
+    let mut source: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(stdin())
+    } else {
+        Box::new(File::open(path)?)
+    };
+    let mut contents = String::new();
+    source.read_to_string(&mut contents)?;
+    Ok(contents)
 }
 
-pub fn read_lines<T>(
+/// Reads all of `path`, relative to [`input_root`] unless absolute. The path
+/// `-` reads from standard input instead.
+pub fn read_text(path: &str) -> io::Result<String> {
+    if path == "-" {
+        read_input(Path::new(path))
+    } else {
+        read_input(&input_root().join(path))
+    }
+}
+
+/// Like [`read_text`], but split into lines.
+pub fn read_file(path: &str) -> io::Result<impl Iterator<Item = String>> {
+    let contents = read_text(path)?;
+    Ok(lines(&contents).collect::<Vec<_>>().into_iter())
+}
+
+/// Parses all of `path` with one of the days' `from_str` functions.
+pub fn parse_file<T>(
+    path: &str,
+    parser: fn(&str) -> Result<T, ParseError>,
+) -> Result<T, InputError> {
+    let text = read_text(path).map_err(|e| InputError::io(path, e))?;
+    Ok(parser(&text).map_err(|e| e.in_file(path))?)
+}
+
+pub fn read_line<T: FromStr>(path: &str) -> Result<Vec<T>, InputError> {
+    let line = read_file(path)
+        .map_err(|e| InputError::io(path, e))?
+        .next()
+        .unwrap_or_default();
+    Ok(parse_line(&line).map_err(|e| e.in_file(path))?)
+}
+
+pub fn parse_line<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    line.trim_end()
+        .split(',')
+        .map(|value| parse_field(line, value, "a number"))
+        .collect()
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = String> + '_ {
+    input.lines().map(|line| line.to_string())
+}
+
+/// Parses every line with `line_parser`, stopping at the first error and
+/// recording which line it was on.
+pub fn try_parse_lines<T>(
+    lines: impl Iterator<Item = String>,
+    line_parser: fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines
+        .enumerate()
+        .map(|(index, line)| line_parser(&line).map_err(|e| e.at_line(index + 1)))
+        .collect()
+}
+
+pub fn try_read_lines<T>(
+    path: &str,
+    line_parser: fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, InputError> {
+    let lines = read_file(path).map_err(|e| InputError::io(path, e))?;
+    Ok(try_parse_lines(lines, line_parser).map_err(|e| e.in_file(path))?)
+}
+
+/// Like [`try_parse_lines`], but skips blank lines rather than handing them
+/// to `line_parser`. Only for formats where blank lines are separators.
+pub fn parse_nonblank_lines<T>(
+    lines: impl Iterator<Item = String>,
+    line_parser: fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| line_parser(&line).map_err(|e| e.at_line(index + 1)))
+        .collect()
+}
+
+pub fn read_nonblank_lines<T>(
     path: &str,
-    line_parser: fn(String) -> Option<T>,
-) -> Result<impl Iterator<Item = T>> {
-    let lines_of_file = read_file(path)?;
-    let lines_as_parsed = lines_of_file.filter_map(line_parser);
-    Ok(lines_as_parsed)
+    line_parser: fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, InputError> {
+    let lines = read_file(path).map_err(|e| InputError::io(path, e))?;
+    Ok(parse_nonblank_lines(lines, line_parser).map_err(|e| e.in_file(path))?)
 }
 
-pub fn read_file_as_i32(path: &str) -> Result<impl Iterator<Item = i32>> {
-    read_lines(path, |line| line.parse().ok())
+pub fn parse_i32(line: &str) -> Result<i32, ParseError> {
+    parse_field(line, line, "a number")
+}
+
+pub fn read_file_as_i32(path: &str) -> Result<impl Iterator<Item = i32>, InputError> {
+    Ok(try_read_lines(path, parse_i32)?.into_iter())
 }
 
 #[cfg(test)]
 mod test {
-    use super::read_file_as_i32;
+    use super::*;
 
     #[test]
     fn simple_file() {
@@ -47,4 +262,90 @@ mod test {
         let as_vec: Vec<i32> = i.collect();
         assert_eq!(vec![1, 2, 3, 4, 5], as_vec);
     }
+
+    #[test]
+    fn absolute_path() {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join(input_root())
+            .join("myfile.txt");
+        let as_vec: Vec<String> = read_file(path.to_str().unwrap()).unwrap().collect();
+        assert_eq!(5, as_vec.len());
+        assert_eq!("1\n2\n3\n4\n5", read_input(&path).unwrap().trim_end());
+    }
+
+    #[test]
+    fn test_parse_i32_error() {
+        assert_eq!(
+            "numbers.txt:1:1: expected a number, found `12x`",
+            try_parse_lines(lines("12x\n4"), parse_i32)
+                .unwrap_err()
+                .in_file("numbers.txt")
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_input_root_override() {
+        assert_eq!(PathBuf::from("fixtures"), root_from(None));
+        assert_eq!(
+            PathBuf::from("/srv/inputs"),
+            root_from(Some(OsString::from("/srv/inputs")))
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let error = read_file_as_i32("no-such-file.txt").err().unwrap();
+        assert!(matches!(&error, InputError::Io { path, .. } if path == "no-such-file.txt"));
+        assert!(error
+            .to_string()
+            .starts_with("cannot read `no-such-file.txt`: "));
+        assert!(matches!(
+            parse_file("myfile.txt", |_| Err::<(), _>(ParseError::new(
+                1, "", "nothing"
+            ))),
+            Err(InputError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_blank_line_error() {
+        assert_eq!(
+            ParseError::new(1, "", "a number").at_line(2),
+            try_parse_lines(lines("1\n\n2"), parse_i32).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_nonblank_lines() {
+        assert_eq!(
+            ParseError::new(1, "three", "a number").at_line(5),
+            parse_nonblank_lines(lines("1\n\n2\n\nthree"), parse_i32).unwrap_err()
+        );
+        assert_eq!(
+            vec![1, 2],
+            parse_nonblank_lines(lines("1\n\n2\n"), parse_i32).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_line_error() {
+        let error = parse_line::<u32>("3,4,x,1").unwrap_err();
+        assert_eq!(ParseError::new(5, "x", "a number"), error);
+        assert_eq!("1:5: expected a number, found `x`", error.to_string());
+    }
+
+    #[test]
+    fn test_try_parse_lines_error() {
+        let error = try_parse_lines(lines("1\n2\nthree\n"), |line| {
+            parse_field::<u32>(line, line, "a number")
+        })
+        .unwrap_err()
+        .in_file("numbers.txt");
+        assert_eq!(
+            "numbers.txt:3:1: expected a number, found `three`",
+            error.to_string()
+        );
+    }
 }
@@ -0,0 +1,260 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use super::{lines, ParseError};
+
+/// A position in a [`Grid`] as `(x, y)`, counting from the top left.
+pub type Point = (usize, usize);
+
+/// Offsets to the positions above, left, right and below.
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+/// Offsets to all eight surrounding positions.
+pub const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangle of cells, stored row by row.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Point) -> T) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut cell)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a grid from its rows, or `None` if they differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses one cell per character, one row per line, reporting where any
+    /// character `cell` rejects is.
+    pub fn parse(
+        input: &str,
+        cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut rows: Vec<Vec<T>> = vec![];
+        for (index, line) in lines(input).enumerate() {
+            let row = line
+                .char_indices()
+                .map(|(i, c)| {
+                    cell(c).ok_or_else(|| ParseError::new(i + 1, &c.to_string(), expected))
+                })
+                .collect::<Result<Vec<T>, _>>()
+                .map_err(|e| e.at_line(index + 1))?;
+            if rows.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(ParseError::new(1, &line, "rows of equal width").at_line(index + 1));
+            }
+            rows.push(row);
+        }
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[point.1 * self.width + point.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.1 * self.width + point.0])
+        } else {
+            None
+        }
+    }
+
+    /// Every position, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` refuses a size of zero, even with nothing to chunk.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(self.height)
+    }
+
+    fn offsets<'a>(
+        &self,
+        (x, y): Point,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Point> + 'a {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(*dx)?;
+            let y = y.checked_add_signed(*dy)?;
+            if x < width && y < height {
+                Some((x, y))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The positions above, left, right and below `point` that are in the grid.
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.offsets(point, &NEIGHBOURS4)
+    }
+
+    /// Like [`Grid::neighbours4`], but with the diagonals too.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.offsets(point, &NEIGHBOURS8)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws the grid one character per cell, one line per row.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&cell).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Parses a grid of single digits, as used by several days.
+pub fn parse_digits(input: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse(input, |c| c.to_digit(10), "a digit")
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("point outside grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).expect("point outside grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_digits() {
+        let grid = parse_digits("123\n456\n").unwrap();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(6, grid[(2, 1)]);
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!("123\n456\n", grid.to_string());
+    }
+
+    #[test]
+    fn test_parse_digits_error() {
+        assert_eq!(
+            ParseError::new(3, "?", "a digit").at_line(2),
+            parse_digits("123\n45?").unwrap_err()
+        );
+        assert_eq!(
+            ParseError::new(1, "45", "rows of equal width").at_line(2),
+            parse_digits("123\n45").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::from_fn(3, 3, |(x, y)| x + y);
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(4, grid.neighbours4((1, 1)).count());
+        assert_eq!(3, grid.neighbours8((2, 2)).count());
+        assert_eq!(8, grid.neighbours8((1, 1)).count());
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::from_rows(vec![vec![true, false], vec![false, true]]).unwrap();
+        assert_eq!("#.\n.#", grid.render(|&on| if on { '#' } else { '.' }));
+        assert!(Grid::from_rows(vec![vec![1], vec![]]).is_none());
+    }
+}
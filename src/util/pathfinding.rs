@@ -0,0 +1,274 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use super::grid::Point;
+
+/// A directed graph with non-negative edge costs.
+pub trait Graph {
+    type Node: Copy + Eq + Hash + Ord;
+
+    /// The nodes reachable from `node` in one step, with what each step costs.
+    fn successors(&self, node: Self::Node) -> Vec<(Self::Node, u32)>;
+
+    /// The nodes that reach `node` in one step, with what each step costs.
+    /// Only bidirectional search needs these.
+    fn predecessors(&self, node: Self::Node) -> Vec<(Self::Node, u32)>;
+}
+
+/// A cheapest route, listing every node from the start to the goal.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Path<N> {
+    pub cost: u32,
+    pub nodes: Vec<N>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Algorithm {
+    Dijkstra,
+    AStar,
+    Bidirectional,
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Algorithm, String> {
+        match name {
+            "dijkstra" => Ok(Algorithm::Dijkstra),
+            "astar" => Ok(Algorithm::AStar),
+            "bidirectional" => Ok(Algorithm::Bidirectional),
+            _ => Err(format!("unknown algorithm `{}`", name)),
+        }
+    }
+}
+
+pub fn manhattan((x1, y1): Point, (x2, y2): Point) -> u32 {
+    (x1.abs_diff(x2) + y1.abs_diff(y2)) as u32
+}
+
+/// How many king's moves apart two points are.
+pub fn chebyshev((x1, y1): Point, (x2, y2): Point) -> u32 {
+    x1.abs_diff(x2).max(y1.abs_diff(y2)) as u32
+}
+
+fn unwind<N: Copy + Eq + Hash>(parents: &HashMap<N, N>, mut node: N) -> Vec<N> {
+    let mut nodes = vec![node];
+    while let Some(parent) = parents.get(&node) {
+        node = *parent;
+        nodes.push(node);
+    }
+    nodes
+}
+
+/// A* search, where `heuristic` must never overestimate the cost to `goal`.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: G::Node,
+    heuristic: impl Fn(G::Node) -> u32,
+) -> Option<Path<G::Node>> {
+    let mut best = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    best.insert(start, 0);
+    queue.push(Reverse((heuristic(start), 0, start)));
+
+    while let Some(Reverse((_, cost, node))) = queue.pop() {
+        if node == goal {
+            let mut nodes = unwind(&parents, goal);
+            nodes.reverse();
+            return Some(Path { cost, nodes });
+        }
+        if best[&node] < cost {
+            continue;
+        }
+        for (next, step) in graph.successors(node) {
+            let cost = cost + step;
+            if best.get(&next).is_none_or(|&b| cost < b) {
+                best.insert(next, cost);
+                parents.insert(next, node);
+                queue.push(Reverse((cost + heuristic(next), cost, next)));
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node, goal: G::Node) -> Option<Path<G::Node>> {
+    astar(graph, start, goal, |_| 0)
+}
+
+struct Frontier<N> {
+    best: HashMap<N, u32>,
+    parents: HashMap<N, N>,
+    queue: BinaryHeap<Reverse<(u32, N)>>,
+}
+
+impl<N: Copy + Eq + Hash + Ord> Frontier<N> {
+    fn new(root: N) -> Frontier<N> {
+        let mut frontier = Frontier {
+            best: HashMap::new(),
+            parents: HashMap::new(),
+            queue: BinaryHeap::new(),
+        };
+        frontier.best.insert(root, 0);
+        frontier.queue.push(Reverse((0, root)));
+        frontier
+    }
+
+    fn top(&self) -> Option<u32> {
+        self.queue.peek().map(|Reverse((cost, _))| *cost)
+    }
+
+    // Settles the nearest node, noting any cheaper meeting with `other`.
+    fn expand(
+        &mut self,
+        other: &Frontier<N>,
+        edges: impl Fn(N) -> Vec<(N, u32)>,
+        meeting: &mut Option<(u32, N)>,
+    ) {
+        let Reverse((cost, node)) = self.queue.pop().unwrap();
+        if self.best[&node] < cost {
+            return;
+        }
+        for (next, step) in edges(node) {
+            let cost = cost + step;
+            if self.best.get(&next).is_none_or(|&b| cost < b) {
+                self.best.insert(next, cost);
+                self.parents.insert(next, node);
+                self.queue.push(Reverse((cost, next)));
+            }
+            if let Some(rest) = other.best.get(&next) {
+                let total = self.best[&next] + rest;
+                if meeting.is_none_or(|(best, _)| total < best) {
+                    *meeting = Some((total, next));
+                }
+            }
+        }
+    }
+}
+
+/// Dijkstra's algorithm run from both ends at once, stopping once the two
+/// searches can no longer improve on where they have met.
+pub fn bidirectional<G: Graph>(graph: &G, start: G::Node, goal: G::Node) -> Option<Path<G::Node>> {
+    if start == goal {
+        return Some(Path {
+            cost: 0,
+            nodes: vec![start],
+        });
+    }
+    let mut forward = Frontier::new(start);
+    let mut backward = Frontier::new(goal);
+    let mut meeting = None;
+
+    while let (Some(top_forward), Some(top_backward)) = (forward.top(), backward.top()) {
+        if meeting.is_some_and(|(cost, _)| top_forward + top_backward >= cost) {
+            break;
+        }
+        if top_forward <= top_backward {
+            forward.expand(&backward, |n| graph.successors(n), &mut meeting);
+        } else {
+            backward.expand(&forward, |n| graph.predecessors(n), &mut meeting);
+        }
+    }
+
+    let (cost, middle) = meeting?;
+    let mut nodes = unwind(&forward.parents, middle);
+    nodes.reverse();
+    nodes.extend(unwind(&backward.parents, middle).into_iter().skip(1));
+    Some(Path { cost, nodes })
+}
+
+/// Finds a cheapest path from `start` to `goal` with the chosen algorithm.
+/// Only A* uses `heuristic`, which must never overestimate the cost to `goal`.
+pub fn find_path<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: G::Node,
+    algorithm: Algorithm,
+    heuristic: impl Fn(G::Node) -> u32,
+) -> Option<Path<G::Node>> {
+    match algorithm {
+        Algorithm::Dijkstra => dijkstra(graph, start, goal),
+        Algorithm::AStar => astar(graph, start, goal, heuristic),
+        Algorithm::Bidirectional => bidirectional(graph, start, goal),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Edges as (from, to, cost).
+    struct Edges(Vec<(Point, Point, u32)>);
+
+    impl Graph for Edges {
+        type Node = Point;
+
+        fn successors(&self, node: Point) -> Vec<(Point, u32)> {
+            self.0
+                .iter()
+                .filter(|(from, _, _)| *from == node)
+                .map(|(_, to, cost)| (*to, *cost))
+                .collect()
+        }
+
+        fn predecessors(&self, node: Point) -> Vec<(Point, u32)> {
+            self.0
+                .iter()
+                .filter(|(_, to, _)| *to == node)
+                .map(|(from, _, cost)| (*from, *cost))
+                .collect()
+        }
+    }
+
+    const ALGORITHMS: [Algorithm; 3] = [
+        Algorithm::Dijkstra,
+        Algorithm::AStar,
+        Algorithm::Bidirectional,
+    ];
+
+    #[test]
+    fn test_find_path() {
+        let graph = Edges(vec![
+            ((0, 0), (1, 0), 1),
+            ((1, 0), (2, 0), 7),
+            ((0, 0), (0, 1), 2),
+            ((0, 1), (1, 1), 2),
+            ((1, 1), (2, 1), 2),
+            ((2, 1), (2, 0), 1),
+            ((2, 0), (0, 0), 2),
+        ]);
+        for algorithm in ALGORITHMS {
+            assert_eq!(
+                Some(Path {
+                    cost: 7,
+                    nodes: vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)],
+                }),
+                find_path(&graph, (0, 0), (2, 0), algorithm, |node| manhattan(
+                    node,
+                    (2, 0)
+                )),
+                "{:?}",
+                algorithm
+            );
+            assert_eq!(None, find_path(&graph, (2, 0), (3, 3), algorithm, |_| 0));
+            assert_eq!(
+                Some(2),
+                find_path(&graph, (2, 0), (0, 0), algorithm, |node| manhattan(
+                    node,
+                    (0, 0)
+                ))
+                .map(|p| p.cost)
+            );
+        }
+    }
+
+    #[test]
+    fn test_algorithm_from_str() {
+        assert_eq!(Ok(Algorithm::AStar), "astar".parse());
+        assert!("bfs".parse::<Algorithm>().is_err());
+    }
+}